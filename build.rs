@@ -0,0 +1,100 @@
+//! Generates the `codes` module from the vendored multicodec table.
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// The multicodec table, see https://github.com/multiformats/multicodec.
+const TABLE: &str = "table.csv";
+
+struct Entry<'a> {
+    name: &'a str,
+    code: u64,
+    status: &'a str,
+    description: &'a str,
+}
+
+fn parse(table: &str) -> Vec<Entry<'_>> {
+    let mut entries = Vec::new();
+    for (i, line) in table.lines().enumerate().skip(1) {
+        let columns: Vec<_> = line.splitn(5, ',').map(str::trim).collect();
+        if columns.len() < 4 {
+            panic!("{}:{}: expected at least 4 columns", TABLE, i + 1);
+        }
+        if columns[1] != "multihash" {
+            continue;
+        }
+        let code = u64::from_str_radix(columns[2].trim_start_matches("0x"), 16)
+            .unwrap_or_else(|err| panic!("{}:{}: invalid code: {}", TABLE, i + 1, err));
+        let status = match columns[3] {
+            "draft" => "Draft",
+            "permanent" => "Permanent",
+            status => panic!("{}:{}: invalid status `{}`", TABLE, i + 1, status),
+        };
+        entries.push(Entry {
+            name: columns[0],
+            code,
+            status,
+            description: columns.get(4).copied().unwrap_or_default(),
+        });
+    }
+    entries.sort_by_key(|entry| entry.code);
+    entries
+}
+
+/// The name of the constant, e.g. `SHA2_256` for `sha2-256`.
+fn const_name(name: &str) -> String {
+    name.to_uppercase().replace('-', "_")
+}
+
+fn generate(entries: &[Entry]) -> String {
+    let mut out = String::new();
+    for entry in entries {
+        writeln!(
+            out,
+            "/// `{}` ({}).",
+            entry.name,
+            entry.status.to_lowercase()
+        )
+        .unwrap();
+        if !entry.description.is_empty() {
+            writeln!(out, "///\n/// {}", entry.description).unwrap();
+        }
+        writeln!(
+            out,
+            "pub const {}: u64 = {:#x};\n",
+            const_name(entry.name),
+            entry.code
+        )
+        .unwrap();
+    }
+    writeln!(
+        out,
+        "/// All multihash entries of the table, sorted by code."
+    )
+    .unwrap();
+    writeln!(out, "pub const TABLE: &[Entry] = &[").unwrap();
+    for entry in entries {
+        writeln!(
+            out,
+            "    Entry {{ name: {:?}, code: {}, status: Status::{} }},",
+            entry.name,
+            const_name(entry.name),
+            entry.status
+        )
+        .unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    out
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", TABLE);
+    let table = fs::read_to_string(TABLE).expect("the multicodec table is readable");
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    fs::write(
+        Path::new(&out_dir).join("codes.rs"),
+        generate(&parse(&table)),
+    )
+    .expect("the generated codes can be written");
+}
@@ -1,39 +1,96 @@
 //! This proc macro derives a custom Multihash code table from a list of hashers.
 //!
 //! The digests are stack allocated with a fixed size. That size needs to be big enough to hold any
-//! of the specified hash digests. This cannot be determined reliably on compile-time, hence it
-//! needs to set manually via the `alloc_size` attribute. Also you might want to set it to bigger
-//! sizes then necessarily needed for backwards/forward compatibility.
+//! of the specified hash digests and is set via the `alloc_size` attribute. You might want to set
+//! it to bigger sizes than necessarily needed for backwards/forward compatibility.
 //!
-//! If you set `#mh(alloc_size = …)` to a too low value, you will get compiler errors. Please note
-//! the the sizes are checked only on a syntactic level and *not* on the type level. This means
-//! that digest need to have a size generic, which is a valid `typenum`, for example `U32` or
-//! `generic_array::typenum::U64`.
+//! If you set `#mh(alloc_size = …)` to a too low value, you will get a compiler error. The sizes
+//! are checked on the type level, hence type aliases work as well. The `no_alloc_size_errors`
+//! attribute is still accepted, but has no effect anymore.
 //!
-//! You can disable those compiler errors with setting the `no_alloc_size_errors` attribute. This
-//! can be useful if you e.g. have specified type aliases for your hash digests and you are sure
-//! you use the correct value for `alloc_size`.
+//! The `digest` attribute is optional, it defaults to the digest of the hasher. The derive
+//! implements `From` for the digest types, which is needed by
+//! `MultihashCode::multihash_from_digest()`, and `FromHasher` for the hashers, which is needed
+//! when finishing a `HashingWriter`. Hashers of other crates need an explicit `digest` though, as
+//! rustc can't tell their inferred digest types apart and reports conflicting implementations.
+//!
+//! The `code` attribute takes any expression that is usable as match pattern, e.g. a literal or
+//! one of the constants of `tiny_multihash::codes`.
+//!
+//! Besides implementing `MultihashCode`, the derive generates an enum named after the code table
+//! with a `Hasher` suffix (e.g. `CodeHasher`). It's returned by `Code::hasher()` and streams data
+//! through the hash function of a code that is only known at runtime, without boxing.
+//!
+//! Every code has a name, which is set with the `name` attribute and defaults to the name of the
+//! variant. It's returned by `Code::name()` and used by the generated `Display` and `FromStr`
+//! implementations. `Code::ALL` lists all codes of the table.
+//!
+//! Additional codes can be given with the repeatable `alias` attribute. They are decoded into the
+//! variant as well, while encoding always uses the canonical `code`. Variants that are marked with
+//! `#[mh(deprecated)]` can still be used, but `Code::is_deprecated()` returns `true` for them, so
+//! that callers can warn about them or refuse them.
+//!
+//! A single variant of the form `Unknown(u64)` can be marked with `#[mh(unknown)]`. Codes that
+//! aren't part of the table are then decoded into that variant instead of returning an error, so
+//! that they round-trip. It has no hash function, hence `try_digest()`, `digest_truncated()`,
+//! `verify()` and `hasher()` error with `Error::UnsupportedCode`. Use `try_digest()` for such code
+//! tables, as the infallible `digest()` panics on the unknown variant. Unknown codes are displayed
+//! with their number, e.g. `unknown(0x16)`.
+//!
+//! With `#[mh(serde)]` the code table implements `Serialize` and `Deserialize`. Codes are
+//! serialized as their name in human-readable formats and as integer otherwise, unknown codes are
+//! always serialized as integer. With `#[mh(scale)]` it implements the SCALE `Encode`, `Decode` and
+//! `MaxEncodedLen` traits, the code is encoded as compact integer. They need the `serde-codec` and
+//! `scale-codec` features of `tiny-multihash` respectively.
 //!
 //! # Example
 //!
 //! ```
+//! use std::convert::TryFrom;
 //! use tiny_multihash::derive::Multihash;
 //! use tiny_multihash::{U32, U64, MultihashCode};
 //!
 //! #[derive(Clone, Copy, Debug, Eq, Multihash, PartialEq)]
 //! #[mh(alloc_size = U64)]
 //! pub enum Code {
-//!     #[mh(code = 0x01, hasher = tiny_multihash::Sha2_256, digest = tiny_multihash::Sha2Digest<U32>)]
+//!     #[mh(code = 0x01, name = "foo", hasher = tiny_multihash::Sha2_256, digest = tiny_multihash::Sha2Digest<U32>)]
 //!     Foo,
-//!     #[mh(code = 0x02, hasher = tiny_multihash::Sha2_512, digest = tiny_multihash::Sha2Digest<U64>)]
+//!     #[mh(code = tiny_multihash::codes::SHA2_512, alias = 0x300013, hasher = tiny_multihash::Sha2_512, digest = tiny_multihash::Sha2Digest<U64>, deprecated)]
 //!     Bar,
 //! }
 //!
 //! let hash = Code::Foo.digest(b"hello world!");
 //! println!("{:02x?}", hash);
+//!
+//! let mut hasher = Code::Foo.hasher().unwrap();
+//! hasher.update(b"hello ");
+//! hasher.update(b"world!");
+//! assert_eq!(hasher.finalize(), hash);
+//!
+//! assert_eq!(Code::Foo.to_string(), "foo");
+//! assert_eq!("Bar".parse::<Code>().unwrap(), Code::Bar);
+//! assert_eq!(Code::ALL, &[Code::Foo, Code::Bar]);
+//! assert_eq!(Code::try_from(0x300013).unwrap(), Code::Bar);
+//! assert_eq!(u64::from(Code::Bar), 0x13);
+//! assert!(Code::Bar.is_deprecated());
+//! ```
+//!
+//! A too small `alloc_size` fails to compile:
+//!
+//! ```compile_fail
+//! use tiny_multihash::derive::Multihash;
+//! use tiny_multihash::U32;
+//!
+//! #[derive(Clone, Copy, Debug, Eq, Multihash, PartialEq)]
+//! #[mh(alloc_size = U32)]
+//! pub enum Code {
+//!     #[mh(code = 0x13, hasher = tiny_multihash::Sha2_512)]
+//!     Sha2_512,
+//! }
 //! ```
 extern crate proc_macro;
 
+mod literal;
 mod multihash;
 mod utils;
 
@@ -41,7 +98,46 @@ use proc_macro::TokenStream;
 use proc_macro_error::proc_macro_error;
 use synstructure::{decl_derive, Structure};
 
-decl_derive!([Multihash, attributes(mh)] => #[proc_macro_error] multihash);
-fn multihash(s: Structure) -> TokenStream {
+decl_derive!([Multihash, attributes(mh)] => #[proc_macro_error] derive_multihash);
+fn derive_multihash(s: Structure) -> TokenStream {
     multihash::multihash(s).into()
 }
+
+/// Parses a multihash literal at compile-time.
+///
+/// The literal is either plain hex or a [multibase] string. Plain hex takes precedence, hence
+/// multibase encoded literals that happen to be valid hex need to use a different base. The code
+/// and the size are validated and the result is a `Multihash`, which can be used in `const` and
+/// `static` items. It fails to compile if the digest doesn't fit into the allocated size, no
+/// matter whether it's used in a constant or in a `let` binding.
+///
+/// # Example
+///
+/// ```
+/// use tiny_multihash::derive::multihash;
+/// use tiny_multihash::{Multihash, U64};
+///
+/// const HASH: Multihash<U64> = multihash!("1203010203");
+/// static OTHER: Multihash<U64> = multihash!("bcibqcaqd");
+///
+/// assert_eq!(HASH.code(), 0x12);
+/// assert_eq!(HASH.digest(), &[1, 2, 3]);
+/// assert_eq!(HASH, OTHER);
+/// ```
+///
+/// A digest that is bigger than the allocated size fails to compile:
+///
+/// ```compile_fail
+/// use tiny_multihash::derive::multihash;
+/// use tiny_multihash::{Multihash, U16};
+///
+/// let hash: Multihash<U16> =
+///     multihash!("1220b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9");
+/// ```
+///
+/// [multibase]: https://github.com/multiformats/multibase
+#[proc_macro]
+#[proc_macro_error]
+pub fn multihash(input: TokenStream) -> TokenStream {
+    literal::multihash(input.into()).into()
+}
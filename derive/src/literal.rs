@@ -0,0 +1,119 @@
+use crate::utils;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Decodes a hex string, returns `None` if it isn't valid hex.
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Decodes a multihash literal into its bytes.
+///
+/// Plain hex takes precedence, everything that isn't plain hex is decoded as multibase.
+fn decode_literal(literal: &str) -> Result<Vec<u8>, String> {
+    match decode_hex(literal) {
+        Some(bytes) => Ok(bytes),
+        None => multibase::decode(literal)
+            .map(|(_base, bytes)| bytes)
+            .map_err(|err| format!("multihash literal is neither hex nor multibase: {}", err)),
+    }
+}
+
+/// Splits the bytes of a multihash into its code and digest.
+fn parse_multihash(bytes: &[u8]) -> Result<(u64, &[u8]), String> {
+    use unsigned_varint::decode;
+
+    let (code, bytes) =
+        decode::u64(bytes).map_err(|err| format!("invalid multihash code: {}", err))?;
+    let (size, digest) =
+        decode::u64(bytes).map_err(|err| format!("invalid multihash size: {}", err))?;
+    if size > u8::MAX as u64 {
+        return Err(format!(
+            "the multihash size {} is bigger than the maximum of 255",
+            size
+        ));
+    }
+    if size != digest.len() as u64 {
+        return Err(format!(
+            "the multihash size is {}, but the digest is {} bytes long",
+            size,
+            digest.len()
+        ));
+    }
+    Ok((code, digest))
+}
+
+pub fn multihash(input: TokenStream) -> TokenStream {
+    let mh_crate = utils::use_crate("tiny-multihash");
+    let literal: syn::LitStr = syn::parse2(input).unwrap_or_else(|_err| {
+        let msg = "expected a string literal: e.g. multihash!(\"1220…\")";
+        #[cfg(test)]
+        panic!("{}", msg);
+        #[cfg(not(test))]
+        proc_macro_error::abort!(_err.span(), msg);
+    });
+
+    let bytes = decode_literal(&literal.value());
+    let parsed = bytes
+        .as_deref()
+        .map_err(Clone::clone)
+        .and_then(parse_multihash);
+    let (code, digest) = parsed.unwrap_or_else(|msg| {
+        #[cfg(test)]
+        panic!("{}", msg);
+        #[cfg(not(test))]
+        proc_macro_error::abort!(literal, msg);
+    });
+
+    quote!(#mh_crate::Multihash::__wrap_literal(#code, &[#(#digest),*]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multihash_literal_hex() {
+        let input = quote!("1203010203");
+        let expected = quote!(tiny_multihash::Multihash::__wrap_literal(
+            18u64,
+            &[1u8, 2u8, 3u8]
+        ));
+        utils::assert_proc_macro(multihash(input), expected);
+    }
+
+    #[test]
+    fn test_multihash_literal_multibase() {
+        // base32 of `1203010203`
+        let input = quote!("bcibqcaqd");
+        let expected = quote!(tiny_multihash::Multihash::__wrap_literal(
+            18u64,
+            &[1u8, 2u8, 3u8]
+        ));
+        utils::assert_proc_macro(multihash(input), expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "the multihash size is 4, but the digest is 3 bytes long")]
+    fn test_multihash_literal_error_size() {
+        multihash(quote!("1204010203"));
+    }
+
+    #[test]
+    #[should_panic(expected = "multihash literal is neither hex nor multibase")]
+    fn test_multihash_literal_error_encoding() {
+        multihash(quote!("xyz"));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a string literal")]
+    fn test_multihash_literal_error_no_string() {
+        multihash(quote!(0x1203));
+    }
+}
@@ -2,9 +2,9 @@ use std::collections::HashSet;
 
 use crate::utils;
 use proc_macro2::TokenStream;
-use quote::quote;
 #[cfg(not(test))]
 use quote::ToTokens;
+use quote::{quote, quote_spanned};
 use syn::parse::{Parse, ParseStream};
 use syn::spanned::Spanned;
 use synstructure::{Structure, VariantInfo};
@@ -12,28 +12,47 @@ use synstructure::{Structure, VariantInfo};
 mod kw {
     use syn::custom_keyword;
 
+    custom_keyword!(alias);
     custom_keyword!(code);
+    custom_keyword!(deprecated);
     custom_keyword!(digest);
     custom_keyword!(hasher);
     custom_keyword!(mh);
+    custom_keyword!(name);
+    custom_keyword!(scale);
+    custom_keyword!(serde);
+    custom_keyword!(unknown);
     custom_keyword!(alloc_size);
     custom_keyword!(no_alloc_size_errors);
 }
 
 /// Attributes for the enum items.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 enum MhAttr {
     Code(utils::Attr<kw::code, syn::Expr>),
+    Alias(utils::Attr<kw::alias, syn::Expr>),
+    Deprecated(#[allow(dead_code)] kw::deprecated),
     Hasher(utils::Attr<kw::hasher, Box<syn::Type>>),
     Digest(utils::Attr<kw::digest, syn::Path>),
+    Name(utils::Attr<kw::name, syn::LitStr>),
+    Unknown(#[allow(dead_code)] kw::unknown),
 }
 
 impl Parse for MhAttr {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         if input.peek(kw::code) {
             Ok(MhAttr::Code(input.parse()?))
+        } else if input.peek(kw::alias) {
+            Ok(MhAttr::Alias(input.parse()?))
+        } else if input.peek(kw::deprecated) {
+            Ok(MhAttr::Deprecated(input.parse()?))
         } else if input.peek(kw::hasher) {
             Ok(MhAttr::Hasher(input.parse()?))
+        } else if input.peek(kw::name) {
+            Ok(MhAttr::Name(input.parse()?))
+        } else if input.peek(kw::unknown) {
+            Ok(MhAttr::Unknown(input.parse()?))
         } else {
             Ok(MhAttr::Digest(input.parse()?))
         }
@@ -41,10 +60,13 @@ impl Parse for MhAttr {
 }
 
 /// Attributes of the top-level derive.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 enum DeriveAttr {
     AllocSize(utils::Attr<kw::alloc_size, syn::Type>),
-    NoAllocSizeErrors(kw::no_alloc_size_errors),
+    NoAllocSizeErrors(#[allow(dead_code)] kw::no_alloc_size_errors),
+    Serde(#[allow(dead_code)] kw::serde),
+    Scale(#[allow(dead_code)] kw::scale),
 }
 
 impl Parse for DeriveAttr {
@@ -53,12 +75,23 @@ impl Parse for DeriveAttr {
             Ok(Self::AllocSize(input.parse()?))
         } else if input.peek(kw::no_alloc_size_errors) {
             Ok(Self::NoAllocSizeErrors(input.parse()?))
+        } else if input.peek(kw::serde) {
+            Ok(Self::Serde(input.parse()?))
+        } else if input.peek(kw::scale) {
+            Ok(Self::Scale(input.parse()?))
         } else {
             Err(syn::Error::new(input.span(), "unknown attribute"))
         }
     }
 }
 
+/// The parsed attributes of the code enum.
+struct CodeEnumAttrs {
+    alloc_size: syn::Type,
+    serde: bool,
+    scale: bool,
+}
+
 struct Params {
     mh_crate: syn::Ident,
     code_enum: syn::Ident,
@@ -68,8 +101,11 @@ struct Params {
 struct Hash {
     ident: syn::Ident,
     code: syn::Expr,
+    aliases: Vec<syn::Expr>,
+    deprecated: bool,
     hasher: Box<syn::Type>,
-    digest: syn::Path,
+    digest: Option<syn::Path>,
+    name: syn::LitStr,
 }
 
 impl Hash {
@@ -83,7 +119,14 @@ impl Hash {
     fn code_from_u64(&self) -> TokenStream {
         let ident = &self.ident;
         let code = &self.code;
-        quote!(#code => Ok(Self::#ident))
+        let aliases = &self.aliases;
+        quote!(#code #(| #aliases)* => Ok(Self::#ident))
+    }
+
+    fn code_is_deprecated(&self) -> TokenStream {
+        let ident = &self.ident;
+        let deprecated = self.deprecated;
+        quote!(Self::#ident => #deprecated)
     }
 
     fn code_digest(&self, params: &Params) -> TokenStream {
@@ -97,10 +140,109 @@ impl Hash {
         })
     }
 
-    fn from_digest(&self, params: &Params) -> TokenStream {
-        let digest = &self.digest;
+    fn code_size(&self) -> TokenStream {
+        let ident = &self.ident;
+        let hasher = &self.hasher;
+        quote!(Self::#ident => #hasher::size())
+    }
+
+    fn code_hasher(&self, hasher_enum: &syn::Ident) -> TokenStream {
+        let ident = &self.ident;
+        quote!(Self::#ident => Ok(#hasher_enum::#ident(Default::default())))
+    }
+
+    fn hasher_variant(&self) -> TokenStream {
+        let ident = &self.ident;
+        let hasher = &self.hasher;
+        quote!(#ident(#hasher))
+    }
+
+    fn hasher_code(&self, params: &Params) -> TokenStream {
+        let ident = &self.ident;
+        let code_enum = &params.code_enum;
+        quote!(Self::#ident(_) => #code_enum::#ident)
+    }
+
+    fn hasher_update(&self) -> TokenStream {
+        let ident = &self.ident;
+        quote!(Self::#ident(hasher) => hasher.update(input))
+    }
+
+    fn hasher_finalize(&self, params: &Params) -> TokenStream {
+        let ident = &self.ident;
+        let code = &self.code;
+        let mh_crate = &params.mh_crate;
+        quote!(Self::#ident(hasher) => {
+           let digest = hasher.finalize();
+           #mh_crate::Multihash::wrap(#code, &digest.as_ref()).unwrap()
+        })
+    }
+
+    fn hasher_reset(&self) -> TokenStream {
+        let ident = &self.ident;
+        quote!(Self::#ident(hasher) => hasher.reset())
+    }
+
+    fn code_name(&self) -> TokenStream {
+        let ident = &self.ident;
+        let name = &self.name;
+        quote!(Self::#ident => #name)
+    }
+
+    fn code_from_name(&self) -> TokenStream {
+        let ident = &self.ident;
+        let name = &self.name;
+        quote!(#name => Ok(Self::#ident))
+    }
+
+    fn assert_alloc_size(&self, params: &Params, alloc_size: &syn::Type) -> TokenStream {
+        let mh_crate = &params.mh_crate;
+        let hasher = &self.hasher;
+        let msg = format!(
+            "the #mh(alloc_size) attribute must not be smaller than the digest size of `{}`",
+            quote!(#hasher).to_string().replace(' ', "")
+        );
+        quote_spanned! {hasher.span()=>
+            assert!(
+                <<#hasher as #mh_crate::Hasher>::Size as #mh_crate::typenum::Unsigned>::USIZE
+                    <= <#alloc_size as #mh_crate::typenum::Unsigned>::USIZE,
+                #msg
+            );
+        }
+    }
+
+    fn code_visit_hasher(&self) -> TokenStream {
+        let ident = &self.ident;
+        let hasher = &self.hasher;
+        quote!(Self::#ident => Ok(visitor.visit::<#hasher>()))
+    }
+
+    fn code_from_hasher(&self, params: &Params) -> TokenStream {
+        let mh_crate = &params.mh_crate;
+        let code_enum = &params.code_enum;
+        let ident = &self.ident;
+        let hasher = &self.hasher;
+        quote! {
+            impl #mh_crate::FromHasher<#hasher> for #code_enum {
+                fn from_hasher() -> Self {
+                    Self::#ident
+                }
+            }
+        }
+    }
+
+    /// The digest defaults to the digest of the hasher.
+    fn digest_into_code(&self, params: &Params) -> TokenStream {
         let code_enum = &params.code_enum;
         let ident = &self.ident;
+        let digest = match &self.digest {
+            Some(digest) => quote!(#digest),
+            None => {
+                let mh_crate = &params.mh_crate;
+                let hasher = &self.hasher;
+                quote!(<#hasher as #mh_crate::StatefulHasher>::Digest)
+            }
+        };
         quote! {
            impl From<&#digest> for #code_enum {
                fn from(digest: &#digest) -> Self {
@@ -114,16 +256,23 @@ impl Hash {
 impl<'a> From<&'a VariantInfo<'a>> for Hash {
     fn from(bi: &'a VariantInfo<'a>) -> Self {
         let mut code = None;
+        let mut aliases = Vec::new();
+        let mut deprecated = false;
         let mut digest = None;
         let mut hasher = None;
+        let mut name = None;
         for attr in bi.ast().attrs {
             let attr: Result<utils::Attrs<MhAttr>, _> = syn::parse2(attr.tokens.clone());
             if let Ok(attr) = attr {
                 for attr in attr.attrs {
                     match attr {
                         MhAttr::Code(attr) => code = Some(attr.value),
+                        MhAttr::Alias(attr) => aliases.push(attr.value),
+                        MhAttr::Deprecated(_) => deprecated = true,
                         MhAttr::Hasher(attr) => hasher = Some(attr.value),
                         MhAttr::Digest(attr) => digest = Some(attr.value),
+                        MhAttr::Name(attr) => name = Some(attr.value),
+                        MhAttr::Unknown(_) => {}
                     }
                 }
             }
@@ -133,39 +282,81 @@ impl<'a> From<&'a VariantInfo<'a>> for Hash {
         let code = code.unwrap_or_else(|| {
             let msg = "Missing code attribute: e.g. #[mh(code = multihash::SHA3_256)]";
             #[cfg(test)]
-            panic!(msg);
+            panic!("{}", msg);
             #[cfg(not(test))]
             proc_macro_error::abort!(ident, msg);
         });
         let hasher = hasher.unwrap_or_else(|| {
             let msg = "Missing hasher attribute: e.g. #[mh(hasher = multihash::Sha2_256)]";
             #[cfg(test)]
-            panic!(msg);
-            #[cfg(not(test))]
-            proc_macro_error::abort!(ident, msg);
-        });
-        let digest = digest.unwrap_or_else(|| {
-            let msg = "Missing digest atttibute: e.g. #[mh(digest = multihash::Sha2Digest<U32>)]";
-            #[cfg(test)]
-            panic!(msg);
+            panic!("{}", msg);
             #[cfg(not(test))]
             proc_macro_error::abort!(ident, msg);
         });
+        // The name defaults to the name of the variant
+        let name = name.unwrap_or_else(|| syn::LitStr::new(&ident.to_string(), ident.span()));
         Self {
             ident,
             code,
+            aliases,
+            deprecated,
             digest,
             hasher,
+            name,
         }
     }
 }
 
+/// Returns `true` if the variant has an `#[mh(unknown)]` attribute.
+fn is_unknown(bi: &VariantInfo) -> bool {
+    bi.ast().attrs.iter().any(|attr| {
+        let attr: Result<utils::Attrs<MhAttr>, _> = syn::parse2(attr.tokens.clone());
+        match attr {
+            Ok(attr) => attr
+                .attrs
+                .iter()
+                .any(|attr| matches!(attr, MhAttr::Unknown(_))),
+            Err(_) => false,
+        }
+    })
+}
+
+/// Returns the variant for unknown codes.
+///
+/// It emits an error if there are several of them or if it doesn't have a single unnamed field.
+fn parse_unknown_variant(s: &Structure) -> Option<syn::Ident> {
+    let mut unknown: Option<syn::Ident> = None;
+    for bi in s.variants().iter().filter(|bi| is_unknown(bi)) {
+        let ident = bi.ast().ident;
+        if unknown.is_some() {
+            let msg = "the #mh(unknown) attribute is defined multiple times";
+            #[cfg(test)]
+            panic!("{}", msg);
+            #[cfg(not(test))]
+            proc_macro_error::abort!(ident, msg);
+        }
+        match bi.ast().fields {
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {}
+            _ => {
+                let msg = "the #mh(unknown) variant must contain the code: e.g. Unknown(u64)";
+                #[cfg(test)]
+                panic!("{}", msg);
+                #[cfg(not(test))]
+                proc_macro_error::abort!(ident, msg);
+            }
+        }
+        unknown = Some(ident.clone());
+    }
+    unknown
+}
+
 /// Parse top-level enum [#mh()] attributes.
 ///
-/// Returns the `alloc_size` and whether errors regarding to `alloc_size` should be reported or not.
-fn parse_code_enum_attrs(ast: &syn::DeriveInput) -> (syn::Type, bool) {
+/// Returns the `alloc_size`.
+fn parse_code_enum_attrs(ast: &syn::DeriveInput) -> CodeEnumAttrs {
     let mut alloc_size = None;
-    let mut no_alloc_size_errors = false;
+    let mut serde = false;
+    let mut scale = false;
 
     for attr in &ast.attrs {
         let derive_attrs: Result<utils::Attrs<DeriveAttr>, _> = syn::parse2(attr.tokens.clone());
@@ -175,24 +366,31 @@ fn parse_code_enum_attrs(ast: &syn::DeriveInput) -> (syn::Type, bool) {
                     DeriveAttr::AllocSize(alloc_size_attr) => {
                         alloc_size = Some(alloc_size_attr.value)
                     }
-                    DeriveAttr::NoAllocSizeErrors(_) => no_alloc_size_errors = true,
+                    // The size is checked at compile-time, hence this attribute is a no-op
+                    DeriveAttr::NoAllocSizeErrors(_) => {}
+                    DeriveAttr::Serde(_) => serde = true,
+                    DeriveAttr::Scale(_) => scale = true,
                 }
             }
         }
     }
     match alloc_size {
-        Some(alloc_size) => (alloc_size, no_alloc_size_errors),
+        Some(alloc_size) => CodeEnumAttrs {
+            alloc_size,
+            serde,
+            scale,
+        },
         None => {
             let msg = "enum is missing `alloc_size` attribute: e.g. #[mh(alloc_size = U64)]";
             #[cfg(test)]
-            panic!(msg);
+            panic!("{}", msg);
             #[cfg(not(test))]
             proc_macro_error::abort!(&ast.ident, msg);
         }
     }
 }
 
-/// Return an error if the same code is used several times.
+/// Return an error if the same code is used several times, either as code or as alias.
 ///
 /// This only checks for string equality, though this should still catch most errors caused by
 /// copy and pasting.
@@ -200,8 +398,10 @@ fn error_code_duplicates(hashes: &[Hash]) {
     // Use a temporary store to determine whether a certain value is unique or not
     let mut uniq = HashSet::new();
 
-    hashes.iter().for_each(|hash| {
-        let code = &hash.code;
+    let codes = hashes
+        .iter()
+        .flat_map(|hash| core::iter::once(&hash.code).chain(&hash.aliases));
+    codes.for_each(|code| {
         let msg = format!(
             "the #mh(code) attribute `{}` is defined multiple times",
             quote!(#code)
@@ -210,13 +410,13 @@ fn error_code_duplicates(hashes: &[Hash]) {
         // It's a duplicate
         if !uniq.insert(code) {
             #[cfg(test)]
-            panic!(msg);
+            panic!("{}", msg);
             #[cfg(not(test))]
             {
                 let already_defined = uniq.get(code).unwrap();
                 let line = already_defined.to_token_stream().span().start().line;
                 proc_macro_error::emit_error!(
-                    &hash.code, msg;
+                    code, msg;
                     note = "previous definition of `{}` at line {}", quote!(#code), line;
                 );
             }
@@ -224,93 +424,123 @@ fn error_code_duplicates(hashes: &[Hash]) {
     });
 }
 
-/// An error that contains a span in order to produce nice error messages.
-#[derive(Debug)]
-struct ParseError(proc_macro2::Span);
-
-/// Parse a path containing a `typenum` unsigned integer (e.g. `U64`) into a u64
-fn parse_unsigned_typenum(typenum_path: &syn::Type) -> Result<u64, ParseError> {
-    match typenum_path {
-        syn::Type::Path(type_path) => match type_path.path.segments.last() {
-            Some(path_segment) => {
-                let typenum_ident = &path_segment.ident;
-                let typenum = typenum_ident.to_string();
-                match typenum.as_str().split_at(1) {
-                    ("U", byte_size) => byte_size
-                        .parse::<u64>()
-                        .map_err(|_| ParseError(typenum_ident.span())),
-                    _ => Err(ParseError(typenum_ident.span())),
-                }
-            }
-            None => Err(ParseError(type_path.path.span())),
-        },
-        _ => Err(ParseError(typenum_path.span())),
-    }
+fn error_name_duplicates(hashes: &[Hash]) {
+    let mut uniq = HashSet::new();
+
+    hashes.iter().for_each(|hash| {
+        let name = hash.name.value();
+        let msg = format!(
+            "the #mh(name) attribute `{}` is defined multiple times",
+            name
+        );
+
+        if !uniq.insert(name) {
+            #[cfg(test)]
+            panic!("{}", msg);
+            #[cfg(not(test))]
+            proc_macro_error::emit_error!(&hash.name, msg);
+        }
+    });
 }
 
-/// Returns the max size as u64.
+/// Serializes the code as its name in human-readable formats and as integer otherwise.
 ///
-/// Emits an error if the `#mh(alloc_size)` attribute doesn't contain a valid unsigned integer
-/// `typenum`.
-fn parse_alloc_size_attribute(alloc_size: &syn::Type) -> u64 {
-    parse_unsigned_typenum(&alloc_size).unwrap_or_else(|_| {
-        let msg = "`alloc_size` attribute must be a `typenum`, e.g. #[mh(alloc_size = U64)]";
-        #[cfg(test)]
-        panic!(msg);
-        #[cfg(not(test))]
-        proc_macro_error::abort!(&alloc_size, msg);
-    })
+/// Unknown codes have no name, they are always serialized as integer.
+fn serde_impl(params: &Params, unknown: Option<&syn::Ident>) -> TokenStream {
+    let mh_crate = &params.mh_crate;
+    let code_enum = &params.code_enum;
+    let visitor = quote::format_ident!("{}Visitor", code_enum);
+    let is_named = match unknown {
+        Some(unknown) => {
+            quote!(serializer.is_human_readable() && !matches!(self, Self::#unknown(_)))
+        }
+        None => quote!(serializer.is_human_readable()),
+    };
+    quote! {
+        impl #mh_crate::serde::Serialize for #code_enum {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: #mh_crate::serde::Serializer,
+            {
+                if #is_named {
+                    serializer.serialize_str(self.name())
+                } else {
+                    serializer.serialize_u64(u64::from(*self))
+                }
+            }
+        }
+
+        impl<'de> #mh_crate::serde::Deserialize<'de> for #code_enum {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: #mh_crate::serde::Deserializer<'de>,
+            {
+                struct #visitor;
+
+                impl<'de> #mh_crate::serde::de::Visitor<'de> for #visitor {
+                    type Value = #code_enum;
+
+                    fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                        f.write_str("a multihash code or its name")
+                    }
+
+                    fn visit_u64<E>(self, code: u64) -> Result<Self::Value, E>
+                    where
+                        E: #mh_crate::serde::de::Error,
+                    {
+                        core::convert::TryFrom::try_from(code).map_err(E::custom)
+                    }
+
+                    fn visit_str<E>(self, name: &str) -> Result<Self::Value, E>
+                    where
+                        E: #mh_crate::serde::de::Error,
+                    {
+                        name.parse().map_err(E::custom)
+                    }
+                }
+
+                if deserializer.is_human_readable() {
+                    deserializer.deserialize_any(#visitor)
+                } else {
+                    deserializer.deserialize_u64(#visitor)
+                }
+            }
+        }
+    }
 }
 
-/// Return a warning/error if the specified alloc_size is smaller than the biggest digest
-fn error_alloc_size(hashes: &[Hash], expected_alloc_size_type: &syn::Type) {
-    let expected_alloc_size = parse_alloc_size_attribute(expected_alloc_size_type);
+/// Encodes the code as compact integer.
+fn scale_impl(params: &Params) -> TokenStream {
+    let mh_crate = &params.mh_crate;
+    let code_enum = &params.code_enum;
+    quote! {
+        impl #mh_crate::parity_scale_codec::Encode for #code_enum {
+            fn encode_to<O>(&self, dest: &mut O)
+            where
+                O: #mh_crate::parity_scale_codec::Output + ?Sized,
+            {
+                let code = #mh_crate::parity_scale_codec::Compact(u64::from(*self));
+                #mh_crate::parity_scale_codec::Encode::encode_to(&code, dest)
+            }
+        }
 
-    let maybe_error: Result<(), ParseError> = hashes
-        .iter()
-        .map(|hash| {
-            // The digest type must have a size parameter of the shape `U<number>`, else we error.
-            match hash.digest.segments.last() {
-                Some(path_segment) => match &path_segment.arguments {
-                    syn::PathArguments::AngleBracketed(arguments) => match arguments.args.last() {
-                        Some(syn::GenericArgument::Type(path)) => {
-                            match parse_unsigned_typenum(&path) {
-                                Ok(max_digest_size) => {
-                                    if max_digest_size > expected_alloc_size {
-                                        let msg = format!("The `#mh(alloc_size) attribute must be bigger than the maximum defined digest size (U{})",
-                                        max_digest_size);
-                                        #[cfg(test)]
-                                        panic!(msg);
-                                        #[cfg(not(test))]
-                                        {
-                                            let digest = &hash.digest.to_token_stream().to_string().replace(" ", "");
-                                            let line = &hash.digest.span().start().line;
-                                            proc_macro_error::emit_error!(
-                                                &expected_alloc_size_type, msg;
-                                                note = "the bigger digest is `{}` at line {}", digest, line;
-                                            );
-                                        }
-                                    }
-                                    Ok(())
-                                },
-                                Err(err) => Err(err),
-                            }
-                        },
-                        _ => Err(ParseError(arguments.args.span())),
-                    },
-                    _ => Err(ParseError(path_segment.span())),
-                },
-                None => Err(ParseError(hash.digest.span())),
-            }
-        }).collect();
-
-    if let Err(_error) = maybe_error {
-        let msg = "Invalid byte size. It must be a unsigned integer typenum, e.g. `U32`";
-        #[cfg(test)]
-        panic!(msg);
-        #[cfg(not(test))]
-        {
-            proc_macro_error::emit_error!(&_error.0, msg);
+        impl #mh_crate::parity_scale_codec::EncodeLike for #code_enum {}
+
+        impl #mh_crate::parity_scale_codec::Decode for #code_enum {
+            fn decode<I: #mh_crate::parity_scale_codec::Input>(
+                input: &mut I,
+            ) -> Result<Self, #mh_crate::parity_scale_codec::Error> {
+                let code: #mh_crate::parity_scale_codec::Compact<u64> =
+                    #mh_crate::parity_scale_codec::Decode::decode(input)?;
+                core::convert::TryFrom::try_from(code.0)
+                    .map_err(|_| "Unsupported multihash code".into())
+            }
+        }
+
+        impl #mh_crate::parity_scale_codec::MaxEncodedLen for #code_enum {
+            fn max_encoded_len() -> usize {
+                <#mh_crate::parity_scale_codec::Compact<u64> as #mh_crate::parity_scale_codec::MaxEncodedLen>::max_encoded_len()
+            }
         }
     }
 }
@@ -318,26 +548,91 @@ fn error_alloc_size(hashes: &[Hash], expected_alloc_size_type: &syn::Type) {
 pub fn multihash(s: Structure) -> TokenStream {
     let mh_crate = utils::use_crate("tiny-multihash");
     let code_enum = &s.ast().ident;
-    let (alloc_size, no_alloc_size_errors) = parse_code_enum_attrs(&s.ast());
-    let hashes: Vec<_> = s.variants().iter().map(Hash::from).collect();
+    let vis = &s.ast().vis;
+    let hasher_enum = quote::format_ident!("{}Hasher", code_enum);
+    let attrs = parse_code_enum_attrs(s.ast());
+    let alloc_size = &attrs.alloc_size;
+    let unknown = parse_unknown_variant(&s);
+    let hashes: Vec<_> = s
+        .variants()
+        .iter()
+        .filter(|bi| !is_unknown(bi))
+        .map(Hash::from)
+        .collect();
 
     error_code_duplicates(&hashes);
-
-    if !no_alloc_size_errors {
-        error_alloc_size(&hashes, &alloc_size);
-    }
+    error_name_duplicates(&hashes);
 
     let params = Params {
         mh_crate: mh_crate.clone(),
         code_enum: code_enum.clone(),
     };
 
-    let code_into_u64 = hashes.iter().map(|h| h.code_into_u64(&params));
+    let mut code_into_u64: Vec<_> = hashes.iter().map(|h| h.code_into_u64(&params)).collect();
     let code_from_u64 = hashes.iter().map(|h| h.code_from_u64());
-    let code_digest = hashes.iter().map(|h| h.code_digest(&params));
-    let from_digest = hashes.iter().map(|h| h.from_digest(&params));
+    let mut code_digest: Vec<_> = hashes.iter().map(|h| h.code_digest(&params)).collect();
+    let mut code_size: Vec<_> = hashes.iter().map(|h| h.code_size()).collect();
+    let mut code_visit_hasher: Vec<_> = hashes.iter().map(|h| h.code_visit_hasher()).collect();
+    let mut code_hasher: Vec<_> = hashes.iter().map(|h| h.code_hasher(&hasher_enum)).collect();
+    let mut code_name: Vec<_> = hashes.iter().map(|h| h.code_name()).collect();
+    let mut code_is_deprecated: Vec<_> = hashes.iter().map(|h| h.code_is_deprecated()).collect();
+    let mut code_from_unknown = quote!(_ => Err(#mh_crate::Error::UnsupportedCode(code)));
+    let mut code_display = quote!(f.write_str(self.name()));
+    // The unknown variant has no hash function. `digest()` can't return an error, the fallible
+    // `try_digest()`, `verify()` and `hasher()` return `Error::UnsupportedCode` instead.
+    if let Some(unknown) = &unknown {
+        let msg = quote!("multihash code {:#x} has no hash function, use `try_digest()`");
+        code_into_u64.push(quote!(#code_enum::#unknown(code) => code));
+        code_digest.push(quote!(Self::#unknown(code) => panic!(#msg, code)));
+        code_size.push(quote!(Self::#unknown(_) => 0));
+        code_visit_hasher
+            .push(quote!(Self::#unknown(code) => Err(#mh_crate::Error::UnsupportedCode(*code))));
+        code_hasher
+            .push(quote!(Self::#unknown(code) => Err(#mh_crate::Error::UnsupportedCode(*code))));
+        code_name.push(quote!(Self::#unknown(_) => "unknown"));
+        code_is_deprecated.push(quote!(Self::#unknown(_) => false));
+        code_from_unknown = quote!(_ => Ok(Self::#unknown(code)));
+        // Unknown codes are displayed with their number, e.g. `unknown(0x16)`
+        code_display = quote! {
+            match self {
+                Self::#unknown(code) => write!(f, "unknown({:#x})", code),
+                _ => f.write_str(self.name()),
+            }
+        };
+    }
+    let hasher_variants = hashes.iter().map(|h| h.hasher_variant());
+    let hasher_code = hashes.iter().map(|h| h.hasher_code(&params));
+    let hasher_update = hashes.iter().map(|h| h.hasher_update());
+    let hasher_finalize = hashes.iter().map(|h| h.hasher_finalize(&params));
+    let hasher_reset = hashes.iter().map(|h| h.hasher_reset());
+    let code_all = hashes.iter().map(|h| &h.ident);
+    let code_from_name = hashes.iter().map(|h| h.code_from_name());
+    let hasher_doc = format!(
+        "Streaming hasher of a [`{}`], returned by [`{}::hasher`].",
+        code_enum, code_enum
+    );
+    let from_hasher = hashes.iter().map(|h| h.code_from_hasher(&params));
+    let from_digest = hashes.iter().map(|h| h.digest_into_code(&params));
+    let assert_alloc_size = hashes
+        .iter()
+        .map(|h| h.assert_alloc_size(&params, alloc_size));
+    let serde = if attrs.serde {
+        serde_impl(&params, unknown.as_ref())
+    } else {
+        quote!()
+    };
+    let scale = if attrs.scale {
+        scale_impl(&params)
+    } else {
+        quote!()
+    };
 
     quote! {
+        // The digests of all hashers must fit into the allocated size
+        const _: () = {
+            #(#assert_alloc_size)*
+        };
+
         impl #mh_crate::MultihashCode for #code_enum {
             type AllocSize = #alloc_size;
 
@@ -357,6 +652,22 @@ pub fn multihash(s: Structure) -> TokenStream {
                 let code = Self::from(&digest);
                 #mh_crate::Multihash::wrap(code.into(), &digest.as_ref()).unwrap()
             }
+
+            fn size(&self) -> u8 {
+                use #mh_crate::Hasher;
+                match self {
+                    #(#code_size,)*
+                }
+            }
+
+            fn visit_hasher<V: #mh_crate::HasherVisitor>(
+                &self,
+                visitor: V,
+            ) -> Result<V::Output, #mh_crate::Error> {
+                match self {
+                    #(#code_visit_hasher,)*
+                }
+            }
         }
 
         impl From<#code_enum> for u64 {
@@ -373,12 +684,107 @@ pub fn multihash(s: Structure) -> TokenStream {
             fn try_from(code: u64) -> Result<Self, Self::Error> {
                 match code {
                     #(#code_from_u64,)*
-                    _ => Err(#mh_crate::Error::UnsupportedCode(code))
+                    #code_from_unknown
                 }
             }
         }
 
+        #(#from_hasher)*
+
         #(#from_digest)*
+
+        impl core::fmt::Display for #code_enum {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                #code_display
+            }
+        }
+
+        impl core::str::FromStr for #code_enum {
+            type Err = #mh_crate::Error;
+
+            fn from_str(name: &str) -> Result<Self, Self::Err> {
+                match name {
+                    #(#code_from_name,)*
+                    _ => Err(#mh_crate::Error::UnsupportedName)
+                }
+            }
+        }
+
+        #[doc = #hasher_doc]
+        #[allow(clippy::large_enum_variant)]
+        #vis enum #hasher_enum {
+            #(
+                #[allow(missing_docs)]
+                #hasher_variants,
+            )*
+        }
+
+        impl #code_enum {
+            /// All codes of the code table, except for unknown codes.
+            #vis const ALL: &'static [Self] = &[#(Self::#code_all),*];
+
+            /// Returns the name of the code.
+            #vis fn name(&self) -> &'static str {
+                match self {
+                    #(#code_name,)*
+                }
+            }
+
+            /// Returns `true` if the code is deprecated.
+            ///
+            /// Deprecated codes are still decoded and hashed, callers can decide to warn about
+            /// them or to refuse them.
+            #vis fn is_deprecated(&self) -> bool {
+                match self {
+                    #(#code_is_deprecated,)*
+                }
+            }
+
+            /// Returns a streaming hasher for the hash function of the code.
+            ///
+            /// It errors with `Error::UnsupportedCode` if the code has no hash function.
+            #vis fn hasher(&self) -> Result<#hasher_enum, #mh_crate::Error> {
+                match self {
+                    #(#code_hasher,)*
+                }
+            }
+        }
+
+        impl #hasher_enum {
+            /// Returns the code of the hasher.
+            pub fn code(&self) -> #code_enum {
+                match self {
+                    #(#hasher_code,)*
+                }
+            }
+
+            /// Consume input and update internal state.
+            pub fn update(&mut self, input: &[u8]) {
+                use #mh_crate::StatefulHasher;
+                match self {
+                    #(#hasher_update,)*
+                }
+            }
+
+            /// Returns the multihash of the input.
+            pub fn finalize(&self) -> #mh_crate::Multihash<#alloc_size> {
+                use #mh_crate::StatefulHasher;
+                match self {
+                    #(#hasher_finalize,)*
+                }
+            }
+
+            /// Reset the internal hasher state.
+            pub fn reset(&mut self) {
+                use #mh_crate::StatefulHasher;
+                match self {
+                    #(#hasher_reset,)*
+                }
+            }
+        }
+
+        #serde
+        #scale
     }
 }
 
@@ -392,14 +798,27 @@ mod tests {
            #[derive(Clone, Multihash)]
            #[mh(alloc_size = U32)]
            pub enum Code {
-               #[mh(code = tiny_multihash::IDENTITY, hasher = tiny_multihash::Identity256, digest = tiny_multihash::IdentityDigest<U32>)]
+               #[mh(code = tiny_multihash::IDENTITY, name = "identity", hasher = tiny_multihash::Identity256, digest = tiny_multihash::IdentityDigest<U32>)]
                Identity256,
                /// Multihash array for hash function.
-               #[mh(code = 0x38b64f, hasher = tiny_multihash::Strobe256, digest = tiny_multihash::StrobeDigest<U32>)]
+               #[mh(code = 0x38b64f, alias = 0x38b650, alias = 0x38b651, hasher = tiny_multihash::Strobe256, deprecated)]
                Strobe256,
             }
         };
         let expected = quote! {
+            const _: () = {
+                assert!(
+                    <<tiny_multihash::Identity256 as tiny_multihash::Hasher>::Size as tiny_multihash::typenum::Unsigned>::USIZE
+                        <= <U32 as tiny_multihash::typenum::Unsigned>::USIZE,
+                    "the #mh(alloc_size) attribute must not be smaller than the digest size of `tiny_multihash::Identity256`"
+                );
+                assert!(
+                    <<tiny_multihash::Strobe256 as tiny_multihash::Hasher>::Size as tiny_multihash::typenum::Unsigned>::USIZE
+                        <= <U32 as tiny_multihash::typenum::Unsigned>::USIZE,
+                    "the #mh(alloc_size) attribute must not be smaller than the digest size of `tiny_multihash::Strobe256`"
+                );
+            };
+
             impl tiny_multihash::MultihashCode for Code {
                type AllocSize = U32;
 
@@ -426,6 +845,24 @@ mod tests {
                    let code = Self::from(&digest);
                    tiny_multihash::Multihash::wrap(code.into(), &digest.as_ref()).unwrap()
                }
+
+               fn size(&self) -> u8 {
+                   use tiny_multihash::Hasher;
+                   match self {
+                       Self::Identity256 => tiny_multihash::Identity256::size(),
+                       Self::Strobe256 => tiny_multihash::Strobe256::size(),
+                   }
+               }
+
+               fn visit_hasher<V: tiny_multihash::HasherVisitor>(
+                   &self,
+                   visitor: V,
+               ) -> Result<V::Output, tiny_multihash::Error> {
+                   match self {
+                       Self::Identity256 => Ok(visitor.visit::<tiny_multihash::Identity256>()),
+                       Self::Strobe256 => Ok(visitor.visit::<tiny_multihash::Strobe256>()),
+                   }
+               }
             }
 
 
@@ -444,22 +881,137 @@ mod tests {
                 fn try_from(code: u64) -> Result<Self, Self::Error> {
                     match code {
                         tiny_multihash::IDENTITY => Ok(Self::Identity256),
-                        0x38b64f => Ok(Self::Strobe256),
+                        0x38b64f | 0x38b650 | 0x38b651 => Ok(Self::Strobe256),
                         _ => Err(tiny_multihash::Error::UnsupportedCode(code))
                     }
                 }
             }
 
+            impl tiny_multihash::FromHasher<tiny_multihash::Identity256> for Code {
+                fn from_hasher() -> Self {
+                    Self::Identity256
+                }
+            }
+            impl tiny_multihash::FromHasher<tiny_multihash::Strobe256> for Code {
+                fn from_hasher() -> Self {
+                    Self::Strobe256
+                }
+            }
+
             impl From<&tiny_multihash::IdentityDigest<U32> > for Code {
                 fn from(digest: &tiny_multihash::IdentityDigest<U32>) -> Self {
                     Self::Identity256
                 }
             }
-            impl From<&tiny_multihash::StrobeDigest<U32> > for Code {
-                fn from(digest: &tiny_multihash::StrobeDigest<U32>) -> Self {
+            impl From<&<tiny_multihash::Strobe256 as tiny_multihash::StatefulHasher>::Digest> for Code {
+                fn from(digest: &<tiny_multihash::Strobe256 as tiny_multihash::StatefulHasher>::Digest) -> Self {
                     Self::Strobe256
                 }
             }
+
+            impl core::fmt::Display for Code {
+                fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                    f.write_str(self.name())
+                }
+            }
+
+            impl core::str::FromStr for Code {
+                type Err = tiny_multihash::Error;
+
+                fn from_str(name: &str) -> Result<Self, Self::Err> {
+                    match name {
+                        "identity" => Ok(Self::Identity256),
+                        "Strobe256" => Ok(Self::Strobe256),
+                        _ => Err(tiny_multihash::Error::UnsupportedName)
+                    }
+                }
+            }
+
+            #[doc = "Streaming hasher of a [`Code`], returned by [`Code::hasher`]."]
+            #[allow(clippy::large_enum_variant)]
+            pub enum CodeHasher {
+                #[allow(missing_docs)]
+                Identity256(tiny_multihash::Identity256),
+                #[allow(missing_docs)]
+                Strobe256(tiny_multihash::Strobe256),
+            }
+
+            impl Code {
+                /// All codes of the code table, except for unknown codes.
+                pub const ALL: &'static [Self] = &[Self::Identity256, Self::Strobe256];
+
+                /// Returns the name of the code.
+                pub fn name(&self) -> &'static str {
+                    match self {
+                        Self::Identity256 => "identity",
+                        Self::Strobe256 => "Strobe256",
+                    }
+                }
+
+                /// Returns `true` if the code is deprecated.
+                ///
+                /// Deprecated codes are still decoded and hashed, callers can decide to warn about
+                /// them or to refuse them.
+                pub fn is_deprecated(&self) -> bool {
+                    match self {
+                        Self::Identity256 => false,
+                        Self::Strobe256 => true,
+                    }
+                }
+
+                /// Returns a streaming hasher for the hash function of the code.
+                ///
+                /// It errors with `Error::UnsupportedCode` if the code has no hash function.
+                pub fn hasher(&self) -> Result<CodeHasher, tiny_multihash::Error> {
+                    match self {
+                        Self::Identity256 => Ok(CodeHasher::Identity256(Default::default())),
+                        Self::Strobe256 => Ok(CodeHasher::Strobe256(Default::default())),
+                    }
+                }
+            }
+
+            impl CodeHasher {
+                /// Returns the code of the hasher.
+                pub fn code(&self) -> Code {
+                    match self {
+                        Self::Identity256(_) => Code::Identity256,
+                        Self::Strobe256(_) => Code::Strobe256,
+                    }
+                }
+
+                /// Consume input and update internal state.
+                pub fn update(&mut self, input: &[u8]) {
+                    use tiny_multihash::StatefulHasher;
+                    match self {
+                        Self::Identity256(hasher) => hasher.update(input),
+                        Self::Strobe256(hasher) => hasher.update(input),
+                    }
+                }
+
+                /// Returns the multihash of the input.
+                pub fn finalize(&self) -> tiny_multihash::Multihash<U32> {
+                    use tiny_multihash::StatefulHasher;
+                    match self {
+                        Self::Identity256(hasher) => {
+                            let digest = hasher.finalize();
+                            tiny_multihash::Multihash::wrap(tiny_multihash::IDENTITY, &digest.as_ref()).unwrap()
+                        },
+                        Self::Strobe256(hasher) => {
+                            let digest = hasher.finalize();
+                            tiny_multihash::Multihash::wrap(0x38b64f, &digest.as_ref()).unwrap()
+                        },
+                    }
+                }
+
+                /// Reset the internal hasher state.
+                pub fn reset(&mut self) {
+                    use tiny_multihash::StatefulHasher;
+                    match self {
+                        Self::Identity256(hasher) => hasher.reset(),
+                        Self::Strobe256(hasher) => hasher.reset(),
+                    }
+                }
+            }
         };
         let derive_input = syn::parse2(input).unwrap();
         let s = Structure::new(&derive_input);
@@ -506,15 +1058,77 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(
-        expected = "enum is missing `alloc_size` attribute: e.g. #[mh(alloc_size = U64)]"
-    )]
-    fn test_multihash_error_no_alloc_size() {
+    fn test_multihash_serde() {
+        let params = Params {
+            mh_crate: syn::parse_quote!(tiny_multihash),
+            code_enum: syn::parse_quote!(Code),
+        };
+        let unknown = syn::parse_quote!(Unknown);
+        let expected = quote! {
+            impl tiny_multihash::serde::Serialize for Code {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: tiny_multihash::serde::Serializer,
+                {
+                    if serializer.is_human_readable() && !matches!(self, Self::Unknown(_)) {
+                        serializer.serialize_str(self.name())
+                    } else {
+                        serializer.serialize_u64(u64::from(*self))
+                    }
+                }
+            }
+
+            impl<'de> tiny_multihash::serde::Deserialize<'de> for Code {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: tiny_multihash::serde::Deserializer<'de>,
+                {
+                    struct CodeVisitor;
+
+                    impl<'de> tiny_multihash::serde::de::Visitor<'de> for CodeVisitor {
+                        type Value = Code;
+
+                        fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                            f.write_str("a multihash code or its name")
+                        }
+
+                        fn visit_u64<E>(self, code: u64) -> Result<Self::Value, E>
+                        where
+                            E: tiny_multihash::serde::de::Error,
+                        {
+                            core::convert::TryFrom::try_from(code).map_err(E::custom)
+                        }
+
+                        fn visit_str<E>(self, name: &str) -> Result<Self::Value, E>
+                        where
+                            E: tiny_multihash::serde::de::Error,
+                        {
+                            name.parse().map_err(E::custom)
+                        }
+                    }
+
+                    if deserializer.is_human_readable() {
+                        deserializer.deserialize_any(CodeVisitor)
+                    } else {
+                        deserializer.deserialize_u64(CodeVisitor)
+                    }
+                }
+            }
+        };
+        utils::assert_proc_macro(serde_impl(&params, Some(&unknown)), expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "the #mh(code) attribute `0x14` is defined multiple times")]
+    fn test_multihash_error_alias_duplicates() {
         let input = quote! {
            #[derive(Clone, Multihash)]
+           #[mh(alloc_size = U32)]
            pub enum Code {
                #[mh(code = 0x14, hasher = tiny_multihash::Sha2_256, digest = tiny_multihash::Sha2Digest<U32>)]
                Sha2_256,
+               #[mh(code = 0x13, alias = 0x14, hasher = tiny_multihash::Sha2_512, digest = tiny_multihash::Sha2Digest<U64>)]
+               Sha2_512,
             }
         };
         let derive_input = syn::parse2(input).unwrap();
@@ -523,16 +1137,16 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(
-        expected = "The `#mh(alloc_size) attribute must be bigger than the maximum defined digest size (U32)"
-    )]
-    fn test_multihash_error_too_small_alloc_size() {
+    #[should_panic(expected = "the #mh(name) attribute `sha2-256` is defined multiple times")]
+    fn test_multihash_error_name_duplicates() {
         let input = quote! {
            #[derive(Clone, Multihash)]
-           #[mh(alloc_size = U16)]
+           #[mh(alloc_size = U32)]
            pub enum Code {
-               #[mh(code = 0x14, hasher = tiny_multihash::Sha2_256, digest = tiny_multihash::Sha2Digest<U32>)]
+               #[mh(code = 0x12, name = "sha2-256", hasher = tiny_multihash::Sha2_256, digest = tiny_multihash::Sha2Digest<U32>)]
                Sha2_256,
+               #[mh(code = 0x13, name = "sha2-256", hasher = tiny_multihash::Sha2_512, digest = tiny_multihash::Sha2Digest<U32>)]
+               Sha2_512,
             }
         };
         let derive_input = syn::parse2(input).unwrap();
@@ -541,16 +1155,48 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(
-        expected = "Invalid byte size. It must be a unsigned integer typenum, e.g. `U32`"
-    )]
-    fn test_multihash_error_digest_invalid_size_type() {
+    fn test_multihash_unknown() {
         let input = quote! {
            #[derive(Clone, Multihash)]
            #[mh(alloc_size = U32)]
            pub enum Code {
-               #[mh(code = 0x14, hasher = tiny_multihash::Sha2_256, digest = tiny_multihash::Sha2Digest<foo>)]
+               #[mh(code = 0x12, hasher = tiny_multihash::Sha2_256)]
                Sha2_256,
+               #[mh(unknown)]
+               Unknown(u64),
+            }
+        };
+        let derive_input = syn::parse2(input).unwrap();
+        let s = Structure::new(&derive_input);
+        let result = multihash(s).to_string();
+        let arms = [
+            quote!(Code::Unknown(code) => code),
+            quote!(_ => Ok(Self::Unknown(code))),
+            quote!(Self::Unknown(_) => 0),
+            quote!(Self::Unknown(code) => Err(tiny_multihash::Error::UnsupportedCode(*code))),
+            quote!(Self::Unknown(_) => "unknown"),
+            quote!(Self::Unknown(code) => write!(f, "unknown({:#x})", code)),
+            quote!(
+                pub const ALL: &'static [Self] = &[Self::Sha2_256];
+            ),
+        ];
+        for arm in arms.iter() {
+            assert!(result.contains(&arm.to_string()), "missing `{}`", arm);
+        }
+        assert!(!result.contains("CodeHasher :: Unknown"));
+    }
+
+    #[test]
+    #[should_panic(expected = "the #mh(unknown) attribute is defined multiple times")]
+    fn test_multihash_error_unknown_duplicates() {
+        let input = quote! {
+           #[derive(Clone, Multihash)]
+           #[mh(alloc_size = U32)]
+           pub enum Code {
+               #[mh(unknown)]
+               Unknown(u64),
+               #[mh(unknown)]
+               Other(u64),
             }
         };
         let derive_input = syn::parse2(input).unwrap();
@@ -559,16 +1205,14 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(
-        expected = "Invalid byte size. It must be a unsigned integer typenum, e.g. `U32`"
-    )]
-    fn test_multihash_error_digest_invalid_size_type2() {
+    #[should_panic(expected = "the #mh(unknown) variant must contain the code: e.g. Unknown(u64)")]
+    fn test_multihash_error_unknown_without_code() {
         let input = quote! {
            #[derive(Clone, Multihash)]
            #[mh(alloc_size = U32)]
            pub enum Code {
-               #[mh(code = 0x14, hasher = tiny_multihash::Sha2_256, digest = tiny_multihash::Sha2Digest<_>)]
-               Sha2_256,
+               #[mh(unknown)]
+               Unknown,
             }
         };
         let derive_input = syn::parse2(input).unwrap();
@@ -578,14 +1222,13 @@ mod tests {
 
     #[test]
     #[should_panic(
-        expected = "Invalid byte size. It must be a unsigned integer typenum, e.g. `U32`"
+        expected = "enum is missing `alloc_size` attribute: e.g. #[mh(alloc_size = U64)]"
     )]
-    fn test_multihash_error_digest_without_typenum() {
+    fn test_multihash_error_no_alloc_size() {
         let input = quote! {
            #[derive(Clone, Multihash)]
-           #[mh(alloc_size = U32)]
            pub enum Code {
-               #[mh(code = 0x14, hasher = tiny_multihash::Sha2_256, digest = Sha2_256Digest)]
+               #[mh(code = 0x14, hasher = tiny_multihash::Sha2_256, digest = tiny_multihash::Sha2Digest<U32>)]
                Sha2_256,
             }
         };
@@ -594,9 +1237,9 @@ mod tests {
         multihash(s);
     }
 
-    // This one does not panic, die to `no_alloc_size_errors`
+    // The deprecated `no_alloc_size_errors` attribute is still accepted
     #[test]
-    fn test_multihash_error_digest_without_typenum_no_alloc_size_errors() {
+    fn test_multihash_no_alloc_size_errors() {
         let input = quote! {
            #[derive(Clone, Multihash)]
            #[mh(alloc_size = U32, no_alloc_size_errors)]
@@ -7,6 +7,7 @@ pub fn use_crate(name: &str) -> syn::Ident {
     syn::Ident::new(&krate, Span::call_site())
 }
 
+#[allow(dead_code)]
 #[derive(Debug)]
 pub struct Attrs<A> {
     pub paren: syn::token::Paren,
@@ -22,6 +23,7 @@ impl<A: Parse> Parse for Attrs<A> {
     }
 }
 
+#[allow(dead_code)]
 #[derive(Debug)]
 pub struct Attr<K, V> {
     pub key: K,
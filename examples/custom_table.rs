@@ -1,14 +1,15 @@
 use std::convert::TryFrom;
 
 use tiny_multihash::derive::Multihash;
-use tiny_multihash::typenum::{U20, U25, U64};
+use tiny_multihash::typenum::{self, U20, U25, U64};
 use tiny_multihash::{
-    Digest, Error, Hasher, Multihash, MultihashCode, Sha2Digest, Sha2_256, Size, StatefulHasher,
+    Digest, Error, FromHasher, Hasher, HasherVisitor, Multihash, MultihashCode, Sha2Digest,
+    Sha2_256, Size, StatefulHasher,
 };
 
 // You can implement a custom hasher. This is a SHA2 256-bit hasher that returns a hash that is
 // truncated to 160 bits.
-#[derive(Default, Debug)]
+#[derive(Clone, Default, Debug)]
 pub struct Sha2_256Truncated20(Sha2_256);
 impl StatefulHasher for Sha2_256Truncated20 {
     type Size = U20;
@@ -29,10 +30,12 @@ impl StatefulHasher for Sha2_256Truncated20 {
 #[derive(Clone, Copy, Debug, Eq, Multihash, PartialEq)]
 #[mh(alloc_size = U64)]
 pub enum Code {
-    /// Example for using a custom hasher which returns truncated hashes
-    #[mh(code = 0x12, hasher = Sha2_256Truncated20, digest = tiny_multihash::Sha2Digest<U20>)]
+    /// Example for using a custom hasher which returns truncated hashes, the digest type is
+    /// inferred from the hasher
+    #[mh(code = 0x12, hasher = Sha2_256Truncated20)]
     Sha2_256Truncated20,
-    /// Example for using a hasher with a bit size that is not exported by default
+    /// Example for using a hasher with a bit size that is not exported by default. Hashers of
+    /// other crates need an explicit digest type
     #[mh(code = 0xb219, hasher = tiny_multihash::Blake2bHasher::<U25>, digest = tiny_multihash::Blake2bDigest<U25>)]
     Blake2b200,
 }
@@ -59,4 +62,14 @@ fn main() {
     Code::try_from(unknown_hash.code())
         .unwrap()
         .digest(b"hashing something new");
+
+    // Any hash can also be truncated without implementing a custom hasher.
+    let truncated_blake_hash = Code::Blake2b200
+        .digest_truncated(b"hello world!", 16)
+        .unwrap();
+    println!("{:02x?}", truncated_blake_hash);
+    let is_truncated = Code::Blake2b200
+        .is_truncated(truncated_blake_hash.size())
+        .unwrap();
+    println!("  truncated: {}", is_truncated);
 }
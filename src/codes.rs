@@ -0,0 +1,92 @@
+//! Multihash codes of the [multicodec table].
+//!
+//! The constants and the [`TABLE`] are generated from the vendored `table.csv` and contain every
+//! entry tagged with `multihash`, independent of which hashers are compiled in. The constants can
+//! be used in the [`Multihash` derive], e.g. `#[mh(code = codes::SHA2_256, …)]`.
+//!
+//! # Example
+//!
+//! ```
+//! use tiny_multihash::codes::{self, Status};
+//!
+//! let entry = codes::by_name("blake2b-256").unwrap();
+//! assert_eq!(entry.code, codes::BLAKE2B_256);
+//! assert_eq!(codes::by_code(0x12).unwrap().name, "sha2-256");
+//! assert_eq!(codes::by_code(codes::SHA2_256).unwrap().status, Status::Permanent);
+//! ```
+//!
+//! [multicodec table]: https://github.com/multiformats/multicodec/blob/master/table.csv
+//! [`Multihash` derive]: crate::derive
+
+/// Status of an entry of the multicodec table.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Status {
+    /// The code may still change or be removed.
+    Draft,
+    /// The code is stable.
+    Permanent,
+}
+
+/// Entry of the multicodec table.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Entry {
+    /// The name of the hash function, e.g. `sha2-256`.
+    pub name: &'static str,
+    /// The multihash code.
+    pub code: u64,
+    /// The status of the code.
+    pub status: Status,
+}
+
+include!(concat!(env!("OUT_DIR"), "/codes.rs"));
+
+/// Looks up the entry of a code.
+pub fn by_code(code: u64) -> Option<&'static Entry> {
+    TABLE
+        .binary_search_by_key(&code, |entry| entry.code)
+        .ok()
+        .map(|i| &TABLE[i])
+}
+
+/// Looks up the entry of a name.
+pub fn by_name(name: &str) -> Option<&'static Entry> {
+    TABLE.iter().find(|entry| entry.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table() {
+        for entries in TABLE.windows(2) {
+            assert!(entries[0].code < entries[1].code, "{:?}", entries);
+        }
+        for entry in TABLE {
+            assert_eq!(by_code(entry.code), Some(entry));
+            assert_eq!(by_name(entry.name), Some(entry));
+        }
+        let count = |prefix: &str| {
+            TABLE
+                .iter()
+                .filter(|entry| entry.name.starts_with(prefix))
+                .count()
+        };
+        assert_eq!(count("blake2b-"), 64);
+        assert_eq!(count("blake2s-"), 32);
+        assert_eq!(count("skein"), 224);
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(IDENTITY, 0x00);
+        assert_eq!(SHA2_256, 0x12);
+        assert_eq!(BLAKE2B_8, 0xb201);
+        assert_eq!(BLAKE2S_256, 0xb260);
+        assert_eq!(SKEIN1024_1024, 0xb3e0);
+        assert_eq!(by_code(KECCAK_256).unwrap().status, Status::Draft);
+        assert_eq!(by_name("sha2-512").unwrap().code, SHA2_512);
+        assert_eq!(by_code(0x3312e7), None);
+        assert_eq!(by_name("sha2"), None);
+    }
+}
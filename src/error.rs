@@ -12,10 +12,22 @@ pub enum Error {
     Io(IoError),
     /// Unsupported multihash code.
     UnsupportedCode(u64),
+    /// Unsupported name of a multihash code.
+    UnsupportedName,
     /// Invalid multihash size.
     InvalidSize(u64),
     /// Invalid varint.
     Varint(DecodeError),
+    /// The output buffer is too small, it needs to hold at least the given number of bytes.
+    InsufficientBuffer(usize),
+    /// Unsupported multibase prefix.
+    UnsupportedBase(char),
+    /// Invalid multibase encoded string.
+    InvalidBaseString,
+    /// Invalid or unsupported OCI digest string.
+    InvalidOciDigest,
+    /// The digest doesn't match the hash of the data.
+    DigestMismatch,
 }
 
 impl core::fmt::Display for Error {
@@ -24,8 +36,16 @@ impl core::fmt::Display for Error {
             #[cfg(feature = "std")]
             Self::Io(err) => write!(f, "{}", err),
             Self::UnsupportedCode(code) => write!(f, "Unsupported multihash code {}.", code),
+            Self::UnsupportedName => write!(f, "Unsupported multihash code name."),
             Self::InvalidSize(size) => write!(f, "Invalid multihash size {}.", size),
             Self::Varint(err) => write!(f, "{}", err),
+            Self::InsufficientBuffer(len) => {
+                write!(f, "Insufficient buffer, {} bytes are needed.", len)
+            }
+            Self::UnsupportedBase(code) => write!(f, "Unsupported multibase prefix {:?}.", code),
+            Self::InvalidBaseString => write!(f, "Invalid multibase string."),
+            Self::InvalidOciDigest => write!(f, "Invalid OCI digest string."),
+            Self::DigestMismatch => write!(f, "Digest mismatch."),
         }
     }
 }
@@ -40,6 +60,12 @@ impl From<IoError> for Error {
     }
 }
 
+impl From<DecodeError> for Error {
+    fn from(err: DecodeError) -> Self {
+        Self::Varint(err)
+    }
+}
+
 #[cfg(feature = "std")]
 impl From<ReadError> for Error {
     fn from(err: ReadError) -> Self {
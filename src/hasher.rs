@@ -1,4 +1,6 @@
 use crate::error::Error;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
 use core::fmt::Debug;
 use generic_array::typenum::marker_traits::Unsigned;
 use generic_array::{ArrayLength, GenericArray};
@@ -56,7 +58,7 @@ pub trait Digest<S: Size>:
         use unsigned_varint::io::read_u64;
 
         let size = read_u64(&mut r)?;
-        if size > S::to_u64() || size > u8::max_value() as u64 {
+        if size > S::to_u64() || size > u8::MAX as u64 {
             return Err(Error::InvalidSize(size));
         }
         let mut digest = GenericArray::default();
@@ -135,9 +137,147 @@ impl<T: StatefulHasher> Hasher for T {
     }
 }
 
+/// Visitor that is called with the hasher type of a multihash code.
+///
+/// It allows using the hasher of a code that is only known at runtime with APIs that are generic
+/// over a [`StatefulHasher`], see [`MultihashCode::visit_hasher`]. The hashers of a code table
+/// are `Clone`, so that they can be used as `DynHasher`.
+///
+/// [`MultihashCode::visit_hasher`]: crate::MultihashCode::visit_hasher
+pub trait HasherVisitor {
+    /// The return type of the visitor.
+    type Output;
+
+    /// Called with the hasher `H`.
+    fn visit<H: StatefulHasher + Clone + 'static>(self) -> Self::Output;
+}
+
+/// Object-safe version of [`StatefulHasher`].
+///
+/// It's implemented for every [`StatefulHasher`] that is `Clone`, so that hashers can be selected
+/// and stored at runtime as `Box<dyn DynHasher>`, e.g. in a [`HasherRegistry`].
+///
+/// # Example
+///
+/// ```
+/// use tiny_multihash::{DynHasher, Hasher, Sha2_256, Sha3_256};
+///
+/// let mut hashers: Vec<Box<dyn DynHasher>> =
+///     vec![Box::new(Sha2_256::default()), Box::new(Sha3_256::default())];
+/// let mut digest = [0; 32];
+/// for hasher in hashers.iter_mut() {
+///     hasher.update(b"hello world");
+///     assert_eq!(hasher.finalize_into(&mut digest), 32);
+/// }
+/// assert_eq!(digest, Sha3_256::digest(b"hello world").as_ref());
+/// ```
+///
+/// [`HasherRegistry`]: crate::HasherRegistry
+#[cfg(feature = "alloc")]
+pub trait DynHasher: Send + Sync {
+    /// Returns the maximum size of the digest.
+    fn size(&self) -> u8;
+
+    /// Consume input and update internal state.
+    fn update(&mut self, input: &[u8]);
+
+    /// Writes the final digest into `out` and returns its length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` is smaller than the digest, [`DynHasher::size`] bytes are always enough.
+    fn finalize_into(&self, out: &mut [u8]) -> usize;
+
+    /// Reset the internal hasher state.
+    fn reset(&mut self);
+
+    /// Clones the hasher including its internal state.
+    fn box_clone(&self) -> Box<dyn DynHasher>;
+}
+
+#[cfg(feature = "alloc")]
+impl<H: StatefulHasher + Clone + 'static> DynHasher for H {
+    fn size(&self) -> u8 {
+        H::Size::to_u8()
+    }
+
+    fn update(&mut self, input: &[u8]) {
+        StatefulHasher::update(self, input)
+    }
+
+    fn finalize_into(&self, out: &mut [u8]) -> usize {
+        let digest = self.finalize();
+        let digest = digest.as_ref();
+        out[..digest.len()].copy_from_slice(digest);
+        digest.len()
+    }
+
+    fn reset(&mut self) {
+        StatefulHasher::reset(self)
+    }
+
+    fn box_clone(&self) -> Box<dyn DynHasher> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Clone for Box<dyn DynHasher> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+/// Creates the hasher of a code as [`DynHasher`].
+#[cfg(feature = "std")]
+pub(crate) struct BoxedHasher;
+
+#[cfg(feature = "std")]
+impl HasherVisitor for BoxedHasher {
+    type Output = Box<dyn DynHasher>;
+
+    fn visit<H: StatefulHasher + Clone + 'static>(self) -> Self::Output {
+        Box::new(H::default())
+    }
+}
+
 /// New type wrapper for a hasher that implements the `std::io::Write` trait.
+///
+/// The written bytes are only hashed, use [`HashingWriter`] to also pass them on to another
+/// writer.
+///
+/// # Example
+///
+/// ```
+/// use std::io::Write;
+/// use tiny_multihash::{Hasher, Sha2_256, WriteHasher};
+///
+/// let mut hasher = WriteHasher::new(Sha2_256::default());
+/// hasher.write_all(b"hello world").unwrap();
+/// assert_eq!(hasher.finalize(), Sha2_256::digest(b"hello world"));
+/// ```
+///
+/// [`HashingWriter`]: crate::HashingWriter
+#[cfg(feature = "std")]
+pub struct WriteHasher<H: Hasher>(pub(crate) H);
+
 #[cfg(feature = "std")]
-pub struct WriteHasher<H: Hasher>(H);
+impl<H: StatefulHasher> WriteHasher<H> {
+    /// Wraps the hasher.
+    pub fn new(hasher: H) -> Self {
+        Self(hasher)
+    }
+
+    /// Returns the digest of the bytes written so far.
+    pub fn finalize(&self) -> H::Digest {
+        self.0.finalize()
+    }
+
+    /// Returns the hasher.
+    pub fn into_inner(self) -> H {
+        self.0
+    }
+}
 
 #[cfg(feature = "std")]
 impl<H: StatefulHasher> std::io::Write for WriteHasher<H> {
@@ -3,6 +3,57 @@ use crate::hasher::{Digest, Size, StatefulHasher};
 use core::convert::TryFrom;
 use generic_array::GenericArray;
 
+/// Implements the serde and SCALE codecs for a digest type.
+macro_rules! impl_digest_codecs {
+    ($name:ident) => {
+        #[cfg(feature = "serde-codec")]
+        impl<S: Size> serde::Serialize for $name<S> {
+            fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+            where
+                Ser: serde::Serializer,
+            {
+                crate::serde_impl::serialize_digest(self.as_ref(), serializer)
+            }
+        }
+
+        #[cfg(feature = "serde-codec")]
+        impl<'de, S: Size> serde::Deserialize<'de> for $name<S> {
+            fn deserialize<De>(deserializer: De) -> Result<Self, De::Error>
+            where
+                De: serde::Deserializer<'de>,
+            {
+                crate::serde_impl::deserialize_digest(deserializer)
+            }
+        }
+
+        #[cfg(feature = "scale-codec")]
+        impl<S: Size> parity_scale_codec::Encode for $name<S> {
+            fn encode_to<O: parity_scale_codec::Output + ?Sized>(&self, dest: &mut O) {
+                crate::scale_impl::encode_digest(self.as_ref(), dest)
+            }
+        }
+
+        #[cfg(feature = "scale-codec")]
+        impl<S: Size> parity_scale_codec::EncodeLike for $name<S> {}
+
+        #[cfg(feature = "scale-codec")]
+        impl<S: Size> parity_scale_codec::Decode for $name<S> {
+            fn decode<I: parity_scale_codec::Input>(
+                input: &mut I,
+            ) -> Result<Self, parity_scale_codec::Error> {
+                crate::scale_impl::decode_digest(input)
+            }
+        }
+
+        #[cfg(feature = "scale-codec")]
+        impl<S: Size> parity_scale_codec::MaxEncodedLen for $name<S> {
+            fn max_encoded_len() -> usize {
+                crate::scale_impl::max_encoded_digest_len::<S>()
+            }
+        }
+    };
+}
+
 macro_rules! derive_digest {
     ($name:ident) => {
         /// Multihash digest.
@@ -47,6 +98,8 @@ macro_rules! derive_digest {
         }
 
         impl<S: Size> Digest<S> for $name<S> {}
+
+        impl_digest_codecs!($name);
     };
 }
 
@@ -56,7 +109,7 @@ macro_rules! derive_hasher_blake {
         derive_digest!($digest);
 
         /// Multihash hasher.
-        #[derive(Debug)]
+        #[derive(Clone, Debug)]
         pub struct $name<S: Size> {
             _marker: PhantomData<S>,
             state: $module::State,
@@ -134,7 +187,7 @@ pub mod blake3 {
     derive_digest!(Blake3Digest);
 
     /// Multihash hasher.
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     pub struct Blake3Hasher<S: Size> {
         _marker: PhantomData<S>,
         hasher: ::blake3::Hasher,
@@ -177,7 +230,7 @@ pub mod blake3 {
 macro_rules! derive_hasher_sha {
     ($module:ty, $name:ident, $size:ty, $digest:ident) => {
         /// Multihash hasher.
-        #[derive(Debug, Default)]
+        #[derive(Clone, Debug, Default)]
         pub struct $name {
             state: $module,
         }
@@ -301,7 +354,7 @@ pub mod identity {
             use unsigned_varint::io::read_u64;
 
             let size = read_u64(&mut r)?;
-            if size > S::to_u64() || size > u8::max_value() as u64 {
+            if size > S::to_u64() || size > u8::MAX as u64 {
                 return Err(Error::InvalidSize(size));
             }
             let mut digest = GenericArray::default();
@@ -310,12 +363,13 @@ pub mod identity {
         }
     }
 
+    impl_digest_codecs!(IdentityDigest);
+
     /// Identity hasher with a maximum size.
     ///
-    /// # Panics
-    ///
-    /// Panics if the input is bigger than the maximum size.
-    #[derive(Debug, Default)]
+    /// Input that is bigger than the maximum size is truncated, like a truncated digest of any other
+    /// hash function.
+    #[derive(Clone, Debug, Default)]
     pub struct IdentityHasher<S: Size> {
         bytes: GenericArray<u8, S>,
         i: usize,
@@ -328,7 +382,7 @@ pub mod identity {
         fn update(&mut self, input: &[u8]) {
             let start = self.i.min(self.bytes.len());
             let end = (self.i + input.len()).min(self.bytes.len());
-            self.bytes[start..end].copy_from_slice(&input);
+            self.bytes[start..end].copy_from_slice(&input[..end - start]);
             self.i = end;
         }
 
@@ -344,9 +398,7 @@ pub mod identity {
 
     /// 32 byte Identity hasher (constrained to 32 bytes).
     ///
-    /// # Panics
-    ///
-    /// Panics if the input is bigger than 32 bytes.
+    /// Input that is bigger than 32 bytes is truncated.
     pub type Identity256 = IdentityHasher<U32>;
 }
 
@@ -365,6 +417,7 @@ pub mod strobe {
     derive_digest!(StrobeDigest);
 
     /// Strobe hasher.
+    #[derive(Clone)]
     pub struct StrobeHasher<S: Size> {
         _marker: PhantomData<S>,
         strobe: Strobe,
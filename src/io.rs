@@ -0,0 +1,355 @@
+//! Adapters that hash the data that is read or written through them.
+use crate::hasher::{BoxedHasher, DynHasher, Size, StatefulHasher};
+use crate::multihash::{digest_matches, FromHasher, Multihash, MultihashCode};
+use crate::Error;
+use std::io::{Error as IoError, ErrorKind, Read, Write};
+
+/// A reader that verifies the data read through it against a multihash.
+///
+/// Every byte is fed into the hasher of the expected multihash's code. Once the inner reader
+/// reaches the end, the digest is compared with the expected one (respecting truncated digests).
+/// On a mismatch the read fails with an [`std::io::Error`] of kind [`ErrorKind::InvalidData`]
+/// that wraps [`Error::DigestMismatch`].
+///
+/// # Example
+///
+/// ```
+/// use std::io::Read;
+/// use tiny_multihash::{Code, MultihashCode, VerifyingReader};
+///
+/// let hash = Code::Sha2_256.digest(b"hello world");
+/// let mut reader = VerifyingReader::<_, Code>::new(&b"hello world"[..], &hash).unwrap();
+/// let mut data = Vec::new();
+/// reader.read_to_end(&mut data).unwrap();
+///
+/// let mut reader = VerifyingReader::<_, Code>::new(&b"hello world!"[..], &hash).unwrap();
+/// assert!(reader.read_to_end(&mut Vec::new()).is_err());
+/// ```
+pub struct VerifyingReader<R, C: MultihashCode> {
+    inner: R,
+    code: C,
+    hasher: Box<dyn DynHasher>,
+    expected: Multihash<C::AllocSize>,
+    len: u64,
+    max_len: Option<u64>,
+}
+
+impl<R: Read, C: MultihashCode> VerifyingReader<R, C> {
+    /// Creates a reader that verifies the data of `inner` against `expected`.
+    ///
+    /// It errors with [`Error::UnsupportedCode`] if the code isn't part of the code table or has no
+    /// hash function and with [`Error::InvalidSize`] if the digest is bigger than what the hash
+    /// function produces.
+    pub fn new<S: Size>(inner: R, expected: &Multihash<S>) -> Result<Self, Error> {
+        let code =
+            C::try_from(expected.code()).map_err(|_| Error::UnsupportedCode(expected.code()))?;
+        let hasher = code.visit_hasher(BoxedHasher)?;
+        code.is_truncated(expected.size())?;
+        Ok(Self {
+            inner,
+            code,
+            hasher,
+            expected: Multihash::wrap(expected.code(), expected.digest())?,
+            len: 0,
+            max_len: None,
+        })
+    }
+
+    /// Limits the number of bytes that are read.
+    ///
+    /// Reading more bytes fails with an [`std::io::Error`] of kind [`ErrorKind::InvalidData`]
+    /// that wraps [`Error::InvalidSize`].
+    pub fn with_max_len(mut self, max_len: u64) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Returns the number of bytes read so far.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if no bytes were read so far.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn verify(&self) -> Result<(), Error> {
+        let mut digest = [0; 255];
+        let len = self.hasher.finalize_into(&mut digest);
+        if digest_matches(self.code.size(), &digest[..len], self.expected.digest()) {
+            Ok(())
+        } else {
+            Err(Error::DigestMismatch)
+        }
+    }
+}
+
+impl<R: Read, C: MultihashCode> Read for VerifyingReader<R, C> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if read == 0 && !buf.is_empty() {
+            self.verify()
+                .map_err(|err| IoError::new(ErrorKind::InvalidData, err))?;
+            return Ok(0);
+        }
+
+        self.len += read as u64;
+        if let Some(max_len) = self.max_len {
+            if self.len > max_len {
+                return Err(IoError::new(
+                    ErrorKind::InvalidData,
+                    Error::InvalidSize(self.len),
+                ));
+            }
+        }
+        self.hasher.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+/// Hashes data with the hasher of a code, it's shared by the async digest readers.
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+pub(crate) struct CodeDigest<C> {
+    code: C,
+    hasher: Box<dyn DynHasher>,
+}
+
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+impl<C: MultihashCode> CodeDigest<C> {
+    /// Creates the hasher of `code`.
+    ///
+    /// It fails with an [`std::io::Error`] of kind [`ErrorKind::InvalidInput`] if the code has no
+    /// hash function.
+    pub(crate) fn new(code: C) -> std::io::Result<Self> {
+        let hasher = code
+            .visit_hasher(BoxedHasher)
+            .map_err(|err| IoError::new(ErrorKind::InvalidInput, err))?;
+        Ok(Self { code, hasher })
+    }
+
+    /// Consume input and update the internal state.
+    pub(crate) fn update(&mut self, input: &[u8]) {
+        self.hasher.update(input);
+    }
+
+    /// Returns the multihash of the input.
+    pub(crate) fn finish(&self) -> Multihash<C::AllocSize> {
+        let mut digest = [0; 255];
+        let len = self.hasher.finalize_into(&mut digest);
+        Multihash::wrap(self.code.into(), &digest[..len]).expect("the digest fits into the code")
+    }
+}
+
+/// A writer that hashes the bytes it passes on to an inner writer.
+///
+/// It computes the multihash of data while writing it in a single pass. With the `futures-io` or
+/// `tokio` feature it is also an `AsyncWrite` if the inner writer is.
+///
+/// # Example
+///
+/// ```
+/// use std::io::Write;
+/// use tiny_multihash::{Code, HashingWriter, MultihashCode, Sha2_256};
+///
+/// let mut writer = HashingWriter::<_, Sha2_256>::new(Vec::new());
+/// writer.write_all(b"hello world").unwrap();
+/// let (bytes, hash) = writer.finish::<Code>();
+/// assert_eq!(bytes, b"hello world");
+/// assert_eq!(hash, Code::Sha2_256.digest(b"hello world"));
+/// ```
+pub struct HashingWriter<W, H> {
+    pub(crate) inner: W,
+    pub(crate) hasher: H,
+}
+
+impl<W, H: StatefulHasher> HashingWriter<W, H> {
+    /// Creates a writer that hashes the bytes written to `inner`.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: H::default(),
+        }
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns the inner writer and the multihash of the bytes written.
+    ///
+    /// The code is looked up in the code table `C` by the type of the hasher.
+    pub fn finish<C>(self) -> (W, Multihash<C::AllocSize>)
+    where
+        C: MultihashCode + FromHasher<H>,
+    {
+        let digest = self.hasher.finalize();
+        let mh = Multihash::wrap(C::from_hasher().into(), digest.as_ref())
+            .expect("the digest fits into the code table");
+        (self.inner, mh)
+    }
+
+    /// Returns the inner writer and the hasher.
+    pub fn into_inner(self) -> (W, H) {
+        (self.inner, self.hasher)
+    }
+}
+
+impl<W: Write, H: StatefulHasher> Write for HashingWriter<W, H> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A reader that hashes the bytes it reads from an inner reader.
+///
+/// It computes the multihash of data while reading it in a single pass. With the `futures-io` or
+/// `tokio` feature it is also an `AsyncRead` if the inner reader is.
+///
+/// # Example
+///
+/// ```
+/// use std::io::Read;
+/// use tiny_multihash::{Code, HashingReader, MultihashCode, Sha2_256};
+///
+/// let mut reader = HashingReader::<_, Sha2_256>::new(&b"hello world"[..]);
+/// let mut data = Vec::new();
+/// reader.read_to_end(&mut data).unwrap();
+/// let (_, hash) = reader.finish::<Code>();
+/// assert_eq!(hash, Code::Sha2_256.digest(b"hello world"));
+/// ```
+pub struct HashingReader<R, H> {
+    pub(crate) inner: R,
+    pub(crate) hasher: H,
+}
+
+impl<R, H: StatefulHasher> HashingReader<R, H> {
+    /// Creates a reader that hashes the bytes read from `inner`.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: H::default(),
+        }
+    }
+
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns the inner reader and the multihash of the bytes read.
+    ///
+    /// The code is looked up in the code table `C` by the type of the hasher.
+    pub fn finish<C>(self) -> (R, Multihash<C::AllocSize>)
+    where
+        C: MultihashCode + FromHasher<H>,
+    {
+        let digest = self.hasher.finalize();
+        let mh = Multihash::wrap(C::from_hasher().into(), digest.as_ref())
+            .expect("the digest fits into the code table");
+        (self.inner, mh)
+    }
+
+    /// Returns the inner reader and the hasher.
+    pub fn into_inner(self) -> (R, H) {
+        (self.inner, self.hasher)
+    }
+}
+
+impl<R: Read, H: StatefulHasher> Read for HashingReader<R, H> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Code;
+
+    const DATA: &[u8] = b"hello world";
+
+    fn read_all<R: Read>(mut reader: R) -> std::io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Ok(data)
+    }
+
+    fn mismatch(err: std::io::Error) -> bool {
+        err.kind() == ErrorKind::InvalidData
+            && matches!(
+                err.into_inner().unwrap().downcast::<Error>().as_deref(),
+                Ok(Error::DigestMismatch)
+            )
+    }
+
+    #[test]
+    fn test_verifying_reader() {
+        for code in [Code::Sha2_256, Code::Blake2b512, Code::Sha3_224].iter() {
+            let hash = code.digest(DATA);
+            let reader = VerifyingReader::<_, Code>::new(DATA, &hash).unwrap();
+            assert_eq!(read_all(reader).unwrap(), DATA);
+
+            let reader = VerifyingReader::<_, Code>::new(&DATA[1..], &hash).unwrap();
+            assert!(mismatch(read_all(reader).unwrap_err()));
+        }
+
+        let truncated = Code::Sha2_256.digest_truncated(DATA, 16).unwrap();
+        let reader = VerifyingReader::<_, Code>::new(DATA, &truncated).unwrap();
+        assert_eq!(read_all(reader).unwrap(), DATA);
+    }
+
+    #[test]
+    fn test_verifying_reader_errors() {
+        let unknown = Multihash::<crate::U64>::wrap(0x99, &[0; 32]).unwrap();
+        assert!(matches!(
+            VerifyingReader::<_, Code>::new(DATA, &unknown),
+            Err(Error::UnsupportedCode(0x99))
+        ));
+
+        let hash = Code::Sha2_256.digest(DATA);
+        let reader = VerifyingReader::<_, Code>::new(DATA, &hash)
+            .unwrap()
+            .with_max_len(5);
+        let err = read_all(reader).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let reader = VerifyingReader::<_, Code>::new(DATA, &hash)
+            .unwrap()
+            .with_max_len(DATA.len() as u64);
+        assert_eq!(read_all(reader).unwrap(), DATA);
+    }
+
+    #[test]
+    fn test_hashing_writer() {
+        use crate::{Sha3_256, WriteHasher};
+
+        let mut writer = HashingWriter::<_, Sha3_256>::new(Vec::new());
+        for chunk in DATA.chunks(3) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.flush().unwrap();
+        let (bytes, hash) = writer.finish::<Code>();
+        assert_eq!(bytes, DATA);
+        assert_eq!(hash, Code::Sha3_256.digest(DATA));
+
+        let mut hasher = WriteHasher::new(Sha3_256::default());
+        std::io::copy(&mut &DATA[..], &mut hasher).unwrap();
+        let digest = hasher.finalize();
+        assert_eq!(Code::multihash_from_digest(&digest), hash);
+        assert_eq!(hasher.into_inner().finalize(), digest);
+    }
+}
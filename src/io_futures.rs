@@ -0,0 +1,137 @@
+//! Implementations of the `futures-io` traits for the hashing adapters.
+use crate::hasher::{StatefulHasher, WriteHasher};
+use crate::io::{CodeDigest, HashingReader, HashingWriter};
+use crate::multihash::{Multihash, MultihashCode};
+use core::future::{poll_fn, Future};
+use core::pin::Pin;
+use core::task::{ready, Context, Poll};
+use futures_io::{AsyncRead, AsyncWrite};
+use std::io::Result;
+
+impl<H: StatefulHasher + Unpin> AsyncWrite for WriteHasher<H> {
+    fn poll_write(self: Pin<&mut Self>, _cx: &mut Context, buf: &[u8]) -> Poll<Result<usize>> {
+        self.get_mut().0.update(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin, H: StatefulHasher + Unpin> AsyncWrite for HashingWriter<W, H> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context, buf: &[u8]) -> Poll<Result<usize>> {
+        let this = self.get_mut();
+        let written = ready!(Pin::new(&mut this.inner).poll_write(cx, buf))?;
+        this.hasher.update(&buf[..written]);
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_close(cx)
+    }
+}
+
+impl<R: AsyncRead + Unpin, H: StatefulHasher + Unpin> AsyncRead for HashingReader<R, H> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context, buf: &mut [u8]) -> Poll<Result<usize>> {
+        let this = self.get_mut();
+        let read = ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+        this.hasher.update(&buf[..read]);
+        Poll::Ready(Ok(read))
+    }
+}
+
+/// The future returned by [`FuturesDigestReader::digest_reader`].
+pub type DigestReaderFuture<'a, S> =
+    Pin<Box<dyn Future<Output = Result<Multihash<S>>> + Send + 'a>>;
+
+/// Hashing of a `futures-io` [`AsyncRead`] with the hasher of a code.
+///
+/// It is implemented for all code tables.
+///
+/// # Example
+///
+/// ```
+/// use tiny_multihash::{Code, FuturesDigestReader, MultihashCode};
+///
+/// # futures::executor::block_on(async {
+/// let reader = futures::io::Cursor::new(b"hello world");
+/// let hash = Code::Sha2_256.digest_reader(reader).await.unwrap();
+/// assert_eq!(hash, Code::Sha2_256.digest(b"hello world"));
+/// # });
+/// ```
+pub trait FuturesDigestReader: MultihashCode {
+    /// Reads `reader` to the end and returns the multihash of the read bytes.
+    ///
+    /// It fails with an [`std::io::Error`] of kind [`std::io::ErrorKind::InvalidInput`] if the code
+    /// has no hash function.
+    fn digest_reader<'a, R>(&self, reader: R) -> DigestReaderFuture<'a, Self::AllocSize>
+    where
+        R: AsyncRead + Unpin + Send + 'a;
+}
+
+impl<C: MultihashCode> FuturesDigestReader for C {
+    fn digest_reader<'a, R>(&self, mut reader: R) -> DigestReaderFuture<'a, Self::AllocSize>
+    where
+        R: AsyncRead + Unpin + Send + 'a,
+    {
+        let digest = CodeDigest::new(*self);
+        Box::pin(async move {
+            let mut digest = digest?;
+            let mut buf = [0; 8192];
+            loop {
+                let read = poll_fn(|cx| Pin::new(&mut reader).poll_read(cx, &mut buf)).await?;
+                if read == 0 {
+                    break;
+                }
+                digest.update(&buf[..read]);
+            }
+            Ok(digest.finish())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Code, Sha2_256};
+    use futures::executor::block_on;
+    use futures::io::{AsyncReadExt, AsyncWriteExt, Cursor};
+
+    const DATA: &[u8] = b"hello world";
+
+    #[test]
+    fn test_futures_adapters() {
+        block_on(async {
+            let mut writer = HashingWriter::<_, Sha2_256>::new(Cursor::new(Vec::new()));
+            for chunk in DATA.chunks(3) {
+                writer.write_all(chunk).await.unwrap();
+            }
+            writer.close().await.unwrap();
+            let (bytes, hash) = writer.finish::<Code>();
+            assert_eq!(bytes.into_inner(), DATA);
+            assert_eq!(hash, Code::Sha2_256.digest(DATA));
+
+            let mut reader = HashingReader::<_, Sha2_256>::new(Cursor::new(DATA));
+            let mut data = Vec::new();
+            reader.read_to_end(&mut data).await.unwrap();
+            assert_eq!(data, DATA);
+            assert_eq!(reader.finish::<Code>().1, hash);
+
+            let mut hasher = WriteHasher::new(Sha2_256::default());
+            hasher.write_all(DATA).await.unwrap();
+            assert_eq!(Code::multihash_from_digest(&hasher.finalize()), hash);
+
+            let digest = Code::Sha2_256.digest_reader(Cursor::new(DATA)).await;
+            assert_eq!(digest.unwrap(), hash);
+        });
+    }
+}
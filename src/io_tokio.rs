@@ -0,0 +1,145 @@
+//! Implementations of the `tokio` IO traits for the hashing adapters.
+use crate::hasher::{StatefulHasher, WriteHasher};
+use crate::io::{CodeDigest, HashingReader, HashingWriter};
+use crate::multihash::{Multihash, MultihashCode};
+use core::future::{poll_fn, Future};
+use core::pin::Pin;
+use core::task::{ready, Context, Poll};
+use std::io::Result;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+impl<H: StatefulHasher + Unpin> AsyncWrite for WriteHasher<H> {
+    fn poll_write(self: Pin<&mut Self>, _cx: &mut Context, buf: &[u8]) -> Poll<Result<usize>> {
+        self.get_mut().0.update(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin, H: StatefulHasher + Unpin> AsyncWrite for HashingWriter<W, H> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context, buf: &[u8]) -> Poll<Result<usize>> {
+        let this = self.get_mut();
+        let written = ready!(Pin::new(&mut this.inner).poll_write(cx, buf))?;
+        this.hasher.update(&buf[..written]);
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+impl<R: AsyncRead + Unpin, H: StatefulHasher + Unpin> AsyncRead for HashingReader<R, H> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context, buf: &mut ReadBuf) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        let filled = buf.filled().len();
+        ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+        this.hasher.update(&buf.filled()[filled..]);
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// The future returned by [`TokioDigestReader::digest_reader`].
+pub type DigestReaderFuture<'a, S> =
+    Pin<Box<dyn Future<Output = Result<Multihash<S>>> + Send + 'a>>;
+
+/// Hashing of a `tokio` [`AsyncRead`] with the hasher of a code.
+///
+/// It is implemented for all code tables.
+///
+/// # Example
+///
+/// ```
+/// use tiny_multihash::{Code, TokioDigestReader, MultihashCode};
+///
+/// # futures::executor::block_on(async {
+/// let hash = Code::Sha2_256.digest_reader(&b"hello world"[..]).await.unwrap();
+/// assert_eq!(hash, Code::Sha2_256.digest(b"hello world"));
+/// # });
+/// ```
+pub trait TokioDigestReader: MultihashCode {
+    /// Reads `reader` to the end and returns the multihash of the read bytes.
+    ///
+    /// It fails with an [`std::io::Error`] of kind [`std::io::ErrorKind::InvalidInput`] if the code
+    /// has no hash function.
+    fn digest_reader<'a, R>(&self, reader: R) -> DigestReaderFuture<'a, Self::AllocSize>
+    where
+        R: AsyncRead + Unpin + Send + 'a;
+}
+
+impl<C: MultihashCode> TokioDigestReader for C {
+    fn digest_reader<'a, R>(&self, mut reader: R) -> DigestReaderFuture<'a, Self::AllocSize>
+    where
+        R: AsyncRead + Unpin + Send + 'a,
+    {
+        let digest = CodeDigest::new(*self);
+        Box::pin(async move {
+            let mut digest = digest?;
+            let mut buf = [0; 8192];
+            loop {
+                let mut read_buf = ReadBuf::new(&mut buf);
+                poll_fn(|cx| Pin::new(&mut reader).poll_read(cx, &mut read_buf)).await?;
+                if read_buf.filled().is_empty() {
+                    break;
+                }
+                digest.update(read_buf.filled());
+            }
+            Ok(digest.finish())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Code, Sha2_256};
+    use tokio::io::{duplex, AsyncReadExt, AsyncWriteExt};
+
+    const DATA: &[u8] = b"hello world";
+
+    #[tokio::test]
+    async fn test_tokio_adapters() {
+        let (client, server) = duplex(4);
+        let mut writer = HashingWriter::<_, Sha2_256>::new(client);
+        let mut reader = HashingReader::<_, Sha2_256>::new(server);
+        let write = async {
+            for chunk in DATA.chunks(3) {
+                writer.write_all(chunk).await.unwrap();
+            }
+            writer.shutdown().await.unwrap();
+        };
+        let mut data = Vec::new();
+        let read = reader.read_to_end(&mut data);
+        let (_, read) = tokio::join!(write, read);
+        read.unwrap();
+        assert_eq!(data, DATA);
+
+        let hash = Code::Sha2_256.digest(DATA);
+        assert_eq!(writer.finish::<Code>().1, hash);
+        assert_eq!(reader.finish::<Code>().1, hash);
+
+        let mut hasher = WriteHasher::new(Sha2_256::default());
+        hasher.write_all(DATA).await.unwrap();
+        assert_eq!(Code::multihash_from_digest(&hasher.finalize()), hash);
+
+        let (mut client, server) = duplex(4);
+        let digest = Code::Sha2_256.digest_reader(server);
+        let write = async {
+            client.write_all(DATA).await.unwrap();
+            drop(client);
+        };
+        let (_, digest) = tokio::join!(write, digest);
+        assert_eq!(digest.unwrap(), hash);
+    }
+}
@@ -17,41 +17,113 @@
 //!
 //! In order to enable all hashers, you can set the `all` feature flag.
 //!
-//! The library has support for `no_std`, if you disable the `std` feature flag.
+//! The library has support for `no_std`, if you disable the `std` feature flag. Multihashes can
+//! then still be parsed from and written into byte slices. The `alloc` feature flag enables the
+//! helpers that return a `Vec` (like [`Multihash::to_bytes`]) and the heap-allocated
+//! [`MultihashBuf`] without requiring all of `std`.
 //!
 //! The `multihash-impl` feature flag enables a default Multihash implementation that contains all
 //! bundled hashers (which may be disabled via the feature flags mentioned above). If only want a
 //! specific subset of hash algorithms or add one which isn't supporte by default, you will likely
 //! disable that feature and enable `derive` in order to be able to use the [`Multihash` derive].
 //!
+//! The `futures-io` and `tokio` feature flags implement the respective async IO traits for the
+//! hashing adapters ([`HashingReader`], [`HashingWriter`] and [`WriteHasher`]) and add an async
+//! `digest_reader` to code tables.
+//!
+//! The `serde-codec` and `scale-codec` feature flags implement serde and SCALE codecs for
+//! [`Multihash`] and the digest types. Multihashes are serialized as multibase strings in
+//! human-readable formats and as their canonical bytes otherwise, digests as hex strings and as
+//! bytes respectively. The SCALE encoding is the compact encoded code followed by the
+//! length-prefixed digest, its maximum length is bounded by `MaxEncodedLen`. The
+//! [`Multihash` derive] implements them for code tables with `#[mh(serde)]` and `#[mh(scale)]`.
+//!
+//! The `rkyv` feature flag implements zero-copy archiving of [`Multihash`] with [rkyv]. The
+//! [`ArchivedMultihash`] can be read directly from the archived bytes and is validated with
+//! `bytecheck`.
+//!
+//! The `minicbor` feature flag implements the [minicbor] traits for [`Multihash`] and
+//! [`MultihashRef`]. They are encoded as CBOR byte string of the canonical multihash bytes, as
+//! used by DAG-CBOR.
+//!
 //! The `test` feature flag enables property based testing features.
 //!
 //! [feature flags]: https://doc.rust-lang.org/cargo/reference/manifest.html#the-features-section
 //! [`Multihash` derive]: crate::derive
+//! [rkyv]: https://rkyv.org
+//! [minicbor]: https://twittner.gitlab.io/minicbor/minicbor
 
 #![deny(missing_docs)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 #[cfg(any(test, feature = "test"))]
 mod arb;
+pub mod codes;
 mod error;
 mod hasher;
 mod hasher_impl;
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "futures-io")]
+mod io_futures;
+#[cfg(feature = "tokio")]
+mod io_tokio;
+#[cfg(feature = "minicbor")]
+mod minicbor_impl;
+mod multibase;
 mod multihash;
+#[cfg(feature = "alloc")]
+mod multihash_buf;
 #[cfg(feature = "multihash-impl")]
 mod multihash_impl;
+mod multihash_ref;
+mod oci;
+#[cfg(feature = "alloc")]
+mod registry;
+#[cfg(feature = "rkyv")]
+mod rkyv_impl;
+#[cfg(feature = "scale-codec")]
+mod scale_impl;
+#[cfg(feature = "serde-codec")]
+pub mod serde_compat;
+#[cfg(feature = "serde-codec")]
+mod serde_impl;
 
 pub use crate::error::{Error, Result};
+#[cfg(feature = "alloc")]
+pub use crate::hasher::DynHasher;
 #[cfg(feature = "std")]
 pub use crate::hasher::WriteHasher;
-pub use crate::hasher::{Digest, Hasher, Size, StatefulHasher};
-pub use crate::multihash::{Multihash, MultihashCode};
+pub use crate::hasher::{Digest, Hasher, HasherVisitor, Size, StatefulHasher};
+#[cfg(feature = "std")]
+pub use crate::io::{HashingReader, HashingWriter, VerifyingReader};
+#[cfg(feature = "futures-io")]
+pub use crate::io_futures::FuturesDigestReader;
+#[cfg(feature = "tokio")]
+pub use crate::io_tokio::TokioDigestReader;
+pub use crate::multibase::Base;
+pub use crate::multihash::{FromHasher, Multihash, MultihashCode};
+#[cfg(feature = "alloc")]
+pub use crate::multihash_buf::MultihashBuf;
+pub use crate::multihash_ref::MultihashRef;
+pub use crate::oci::{OciAlgorithm, OciDigest};
+#[cfg(feature = "alloc")]
+pub use crate::registry::HasherRegistry;
+#[cfg(feature = "rkyv")]
+pub use crate::rkyv_impl::ArchivedMultihash;
 pub use generic_array::typenum::{self, U128, U16, U20, U28, U32, U48, U64};
+#[cfg(feature = "scale-codec")]
+pub use parity_scale_codec;
+#[cfg(feature = "serde-codec")]
+pub use serde;
 #[cfg(feature = "derive")]
 pub use tiny_multihash_derive as derive;
 
 #[cfg(feature = "multihash-impl")]
-pub use crate::multihash_impl::Code;
+pub use crate::multihash_impl::{Code, CodeHasher};
 
 #[cfg(feature = "blake2b")]
 pub use crate::hasher_impl::blake2b::{Blake2b256, Blake2b512, Blake2bDigest, Blake2bHasher};
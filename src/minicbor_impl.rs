@@ -0,0 +1,133 @@
+//! [minicbor] support for multihashes.
+//!
+//! Multihashes are encoded as CBOR byte string that contains the canonical multihash bytes, like
+//! DAG-CBOR does.
+//!
+//! [minicbor]: https://twittner.gitlab.io/minicbor/minicbor
+use minicbor::data::Type;
+use minicbor::decode::{self, Decoder};
+use minicbor::encode::{self, Encoder, Write};
+use minicbor::{CborLen, Decode, Encode};
+
+use crate::hasher::Size;
+use crate::multihash::{encoded_len, write_multihash_slice, Multihash, MAX_ENCODED_LEN};
+use crate::multihash_ref::MultihashRef;
+
+impl<C, S: Size> Encode<C> for Multihash<S> {
+    fn encode<W: Write>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut C,
+    ) -> Result<(), encode::Error<W::Error>> {
+        MultihashRef::from(self).encode(e, ctx)
+    }
+}
+
+impl<C, S: Size> CborLen<C> for Multihash<S> {
+    fn cbor_len(&self, ctx: &mut C) -> usize {
+        MultihashRef::from(self).cbor_len(ctx)
+    }
+}
+
+/// The multihash is decoded with the same validation as [`Multihash::from_bytes`].
+///
+/// Definite-length byte strings are parsed in place, indefinite-length ones are concatenated
+/// first.
+impl<'b, C, S: Size> Decode<'b, C> for Multihash<S> {
+    fn decode(d: &mut Decoder<'b>, _ctx: &mut C) -> Result<Self, decode::Error> {
+        let pos = d.position();
+        let mut buf = [0; MAX_ENCODED_LEN];
+        let bytes = if d.datatype()? == Type::BytesIndef {
+            let mut len = 0;
+            for chunk in d.bytes_iter()? {
+                let chunk = chunk?;
+                buf.get_mut(len..len + chunk.len())
+                    .ok_or_else(|| decode::Error::message("multihash is too long").at(pos))?
+                    .copy_from_slice(chunk);
+                len += chunk.len();
+            }
+            &buf[..len]
+        } else {
+            d.bytes()?
+        };
+        Multihash::from_bytes(bytes)
+            .map_err(|_| decode::Error::message("invalid multihash").at(pos))
+    }
+}
+
+impl<C> Encode<C> for MultihashRef<'_> {
+    fn encode<W: Write>(
+        &self,
+        e: &mut Encoder<W>,
+        _ctx: &mut C,
+    ) -> Result<(), encode::Error<W::Error>> {
+        let mut bytes = [0; MAX_ENCODED_LEN];
+        let len = write_multihash_slice(&mut bytes, self.code(), self.digest())
+            .map_err(|_| encode::Error::message("invalid multihash"))?;
+        e.bytes(&bytes[..len])?;
+        Ok(())
+    }
+}
+
+impl<C> CborLen<C> for MultihashRef<'_> {
+    fn cbor_len(&self, ctx: &mut C) -> usize {
+        let len = encoded_len(self.code(), self.digest().len());
+        (len as u64).cbor_len(ctx) + len
+    }
+}
+
+/// The digest is borrowed from the input, hence only definite-length byte strings are supported,
+/// as required by DAG-CBOR.
+impl<'b, C> Decode<'b, C> for MultihashRef<'b> {
+    fn decode(d: &mut Decoder<'b>, _ctx: &mut C) -> Result<Self, decode::Error> {
+        let pos = d.position();
+        MultihashRef::from_bytes(d.bytes()?)
+            .map_err(|_| decode::Error::message("invalid multihash").at(pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Code, Multihash, MultihashCode, MultihashRef, U16, U64};
+
+    #[test]
+    fn test_multihash() {
+        let mh = Code::Sha2_256.digest(b"hello world");
+        let cbor = minicbor::to_vec(mh).unwrap();
+        // Major type 2 (byte string) with a one byte length
+        assert_eq!(&cbor[..2], &[0x58, 34]);
+        assert_eq!(&cbor[2..], &mh.to_bytes()[..]);
+        assert_eq!(cbor.len(), minicbor::len(mh));
+        assert_eq!(minicbor::decode::<Multihash<U64>>(&cbor).unwrap(), mh);
+        assert!(minicbor::decode::<Multihash<U16>>(&cbor).is_err());
+
+        let mh_ref = minicbor::decode::<MultihashRef>(&cbor).unwrap();
+        assert_eq!(mh_ref, mh);
+        assert_eq!(mh_ref.digest().as_ptr(), cbor[4..].as_ptr());
+        assert_eq!(minicbor::to_vec(mh_ref).unwrap(), cbor);
+        assert_eq!(minicbor::len(mh_ref), cbor.len());
+    }
+
+    #[test]
+    fn test_invalid() {
+        // Not a byte string
+        assert!(minicbor::decode::<Multihash<U64>>(&[0x12]).is_err());
+        // The digest is shorter than the size
+        let cbor = [0x44, 0x12, 0x04, 0x01, 0x02];
+        assert!(minicbor::decode::<Multihash<U64>>(&cbor).is_err());
+        assert!(minicbor::decode::<MultihashRef>(&cbor).is_err());
+        // Trailing bytes
+        let cbor = [0x45, 0x12, 0x02, 0x01, 0x02, 0x03];
+        assert!(minicbor::decode::<Multihash<U64>>(&cbor).is_err());
+        assert!(minicbor::decode::<MultihashRef>(&cbor).is_err());
+    }
+
+    #[test]
+    fn test_indefinite_length() {
+        let cbor = [0x5f, 0x42, 0x12, 0x02, 0x42, 0x01, 0x02, 0xff];
+        let mh = minicbor::decode::<Multihash<U64>>(&cbor).unwrap();
+        assert_eq!(mh, Multihash::<U64>::wrap(0x12, &[1, 2]).unwrap());
+        // Indefinite-length byte strings can't be borrowed
+        assert!(minicbor::decode::<MultihashRef>(&cbor).is_err());
+    }
+}
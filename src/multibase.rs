@@ -0,0 +1,410 @@
+//! Allocation-free [multibase] encoding and decoding of the bases that are commonly used for
+//! multihashes.
+//!
+//! [multibase]: https://github.com/multiformats/multibase
+use crate::Error;
+
+const BASE16_LOWER: &[u8; 16] = b"0123456789abcdef";
+const BASE16_UPPER: &[u8; 16] = b"0123456789ABCDEF";
+const BASE32_LOWER: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+const BASE32_UPPER: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const BASE36_LOWER: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const BASE36_UPPER: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const BASE58_BTC: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE64_URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// The multibase encodings that are supported for the textual representation of a multihash.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Base {
+    /// Hexadecimal (lowercase), prefix `f`.
+    Base16Lower,
+    /// Hexadecimal (uppercase), prefix `F`.
+    Base16Upper,
+    /// RFC4648 base32 without padding (lowercase), prefix `b`.
+    Base32Lower,
+    /// RFC4648 base32 without padding (uppercase), prefix `B`.
+    Base32Upper,
+    /// RFC4648 base32 with padding (lowercase), prefix `c`.
+    Base32PadLower,
+    /// RFC4648 base32 with padding (uppercase), prefix `C`.
+    Base32PadUpper,
+    /// Base36 (lowercase), prefix `k`.
+    Base36Lower,
+    /// Base36 (uppercase), prefix `K`.
+    Base36Upper,
+    /// Base58 with the bitcoin alphabet, prefix `z`.
+    Base58Btc,
+    /// RFC4648 base64url without padding, prefix `u`.
+    Base64Url,
+    /// RFC4648 base64url with padding, prefix `U`.
+    Base64UrlPad,
+}
+
+/// How the bytes are mapped to characters.
+enum Encoding {
+    /// Every character encodes a fixed number of bits, optionally padded to a multiple of
+    /// `pad_to` characters.
+    Bits {
+        alphabet: &'static [u8],
+        bits: u32,
+        pad_to: Option<usize>,
+    },
+    /// The whole input is interpreted as a big-endian number.
+    BigInt { alphabet: &'static [u8] },
+}
+
+impl Base {
+    /// Returns the multibase prefix character.
+    pub fn code(&self) -> char {
+        match self {
+            Self::Base16Lower => 'f',
+            Self::Base16Upper => 'F',
+            Self::Base32Lower => 'b',
+            Self::Base32Upper => 'B',
+            Self::Base32PadLower => 'c',
+            Self::Base32PadUpper => 'C',
+            Self::Base36Lower => 'k',
+            Self::Base36Upper => 'K',
+            Self::Base58Btc => 'z',
+            Self::Base64Url => 'u',
+            Self::Base64UrlPad => 'U',
+        }
+    }
+
+    /// Returns the base for a multibase prefix character.
+    pub fn from_code(code: char) -> Result<Self, Error> {
+        match code {
+            'f' => Ok(Self::Base16Lower),
+            'F' => Ok(Self::Base16Upper),
+            'b' => Ok(Self::Base32Lower),
+            'B' => Ok(Self::Base32Upper),
+            'c' => Ok(Self::Base32PadLower),
+            'C' => Ok(Self::Base32PadUpper),
+            'k' => Ok(Self::Base36Lower),
+            'K' => Ok(Self::Base36Upper),
+            'z' => Ok(Self::Base58Btc),
+            'u' => Ok(Self::Base64Url),
+            'U' => Ok(Self::Base64UrlPad),
+            _ => Err(Error::UnsupportedBase(code)),
+        }
+    }
+
+    fn encoding(&self) -> Encoding {
+        let bits = |alphabet: &'static [u8], bits, pad_to| Encoding::Bits {
+            alphabet,
+            bits,
+            pad_to,
+        };
+        match self {
+            Self::Base16Lower => bits(BASE16_LOWER, 4, None),
+            Self::Base16Upper => bits(BASE16_UPPER, 4, None),
+            Self::Base32Lower => bits(BASE32_LOWER, 5, None),
+            Self::Base32Upper => bits(BASE32_UPPER, 5, None),
+            Self::Base32PadLower => bits(BASE32_LOWER, 5, Some(8)),
+            Self::Base32PadUpper => bits(BASE32_UPPER, 5, Some(8)),
+            Self::Base36Lower => Encoding::BigInt {
+                alphabet: BASE36_LOWER,
+            },
+            Self::Base36Upper => Encoding::BigInt {
+                alphabet: BASE36_UPPER,
+            },
+            Self::Base58Btc => Encoding::BigInt {
+                alphabet: BASE58_BTC,
+            },
+            Self::Base64Url => bits(BASE64_URL, 6, None),
+            Self::Base64UrlPad => bits(BASE64_URL, 6, Some(4)),
+        }
+    }
+
+    /// Returns an upper bound of the length of the encoded string (including the prefix) for an
+    /// input of `len` bytes.
+    pub fn max_encoded_len(&self, len: usize) -> usize {
+        let encoded = match self.encoding() {
+            Encoding::Bits { bits, pad_to, .. } => {
+                let chars = (len * 8).div_ceil(bits as usize);
+                match pad_to {
+                    Some(pad_to) => chars.div_ceil(pad_to) * pad_to,
+                    None => chars,
+                }
+            }
+            // log(256) / log(36) < 1.55 and log(256) / log(58) < 1.37
+            Encoding::BigInt { alphabet } if alphabet.len() == 36 => len * 155 / 100 + 1,
+            Encoding::BigInt { .. } => len * 137 / 100 + 1,
+        };
+        1 + encoded
+    }
+}
+
+/// Position of a character within the alphabet.
+fn position(alphabet: &[u8], char: u8) -> Result<u32, Error> {
+    alphabet
+        .iter()
+        .position(|&c| c == char)
+        .map(|pos| pos as u32)
+        .ok_or(Error::InvalidBaseString)
+}
+
+/// Encodes `input` as multibase string (including the prefix) into `out`.
+///
+/// Returns the number of bytes written.
+pub(crate) fn encode(base: Base, input: &[u8], out: &mut [u8]) -> Result<usize, Error> {
+    let max_len = base.max_encoded_len(input.len());
+    if out.len() < max_len {
+        return Err(Error::InsufficientBuffer(max_len));
+    }
+    out[0] = base.code() as u8;
+    let len = match base.encoding() {
+        Encoding::Bits {
+            alphabet,
+            bits,
+            pad_to,
+        } => encode_bits(alphabet, bits, pad_to, input, &mut out[1..]),
+        Encoding::BigInt { alphabet } => encode_bigint(alphabet, input, &mut out[1..]),
+    };
+    Ok(1 + len)
+}
+
+fn encode_bits(
+    alphabet: &[u8],
+    bits: u32,
+    pad_to: Option<usize>,
+    input: &[u8],
+    out: &mut [u8],
+) -> usize {
+    let mask = (1 << bits) - 1;
+    let mut len = 0;
+    let mut acc: u32 = 0;
+    let mut acc_bits = 0;
+    for byte in input {
+        acc = (acc << 8) | u32::from(*byte);
+        acc_bits += 8;
+        while acc_bits >= bits {
+            acc_bits -= bits;
+            out[len] = alphabet[((acc >> acc_bits) & mask) as usize];
+            len += 1;
+        }
+        acc &= (1 << acc_bits) - 1;
+    }
+    if acc_bits > 0 {
+        out[len] = alphabet[((acc << (bits - acc_bits)) & mask) as usize];
+        len += 1;
+    }
+    if let Some(pad_to) = pad_to {
+        while len % pad_to != 0 {
+            out[len] = b'=';
+            len += 1;
+        }
+    }
+    len
+}
+
+fn encode_bigint(alphabet: &[u8], input: &[u8], out: &mut [u8]) -> usize {
+    let base = alphabet.len() as u32;
+    let zeros = input.iter().take_while(|&&byte| byte == 0).count();
+    // The digits are accumulated in little-endian order behind the leading zeros
+    let digits = &mut out[zeros..];
+    let mut len = 0;
+    for byte in &input[zeros..] {
+        let mut carry = u32::from(*byte);
+        for digit in digits[..len].iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % base) as u8;
+            carry /= base;
+        }
+        while carry > 0 {
+            digits[len] = (carry % base) as u8;
+            len += 1;
+            carry /= base;
+        }
+    }
+    digits[..len].reverse();
+    for digit in digits[..len].iter_mut() {
+        *digit = alphabet[*digit as usize];
+    }
+    for zero in out[..zeros].iter_mut() {
+        *zero = alphabet[0];
+    }
+    zeros + len
+}
+
+/// Decodes a multibase string into `out`.
+///
+/// Returns the base and the decoded bytes.
+pub(crate) fn decode<'a>(input: &str, out: &'a mut [u8]) -> Result<(Base, &'a [u8]), Error> {
+    let mut chars = input.chars();
+    let base = Base::from_code(chars.next().ok_or(Error::InvalidBaseString)?)?;
+    let len = decode_base(base, chars.as_str(), out)?;
+    Ok((base, &out[..len]))
+}
+
+/// Decodes a string of the given base (without the multibase prefix) into `out`.
+///
+/// Returns the number of bytes written.
+pub(crate) fn decode_base(base: Base, input: &str, out: &mut [u8]) -> Result<usize, Error> {
+    let input = input.as_bytes();
+    match base.encoding() {
+        Encoding::Bits {
+            alphabet,
+            bits,
+            pad_to,
+        } => decode_bits(alphabet, bits, pad_to, input, out),
+        Encoding::BigInt { alphabet } => decode_bigint(alphabet, input, out),
+    }
+}
+
+fn decode_bits(
+    alphabet: &[u8],
+    bits: u32,
+    pad_to: Option<usize>,
+    input: &[u8],
+    out: &mut [u8],
+) -> Result<usize, Error> {
+    let input = match pad_to {
+        Some(pad_to) => {
+            if !input.len().is_multiple_of(pad_to) {
+                return Err(Error::InvalidBaseString);
+            }
+            let unpadded = input.len() - input.iter().rev().take_while(|&&c| c == b'=').count();
+            &input[..unpadded]
+        }
+        None => input,
+    };
+    let mut len = 0;
+    let mut acc: u32 = 0;
+    let mut acc_bits = 0;
+    for char in input {
+        acc = (acc << bits) | position(alphabet, *char)?;
+        acc_bits += bits;
+        if acc_bits >= 8 {
+            acc_bits -= 8;
+            if len == out.len() {
+                return Err(Error::InsufficientBuffer(len + 1));
+            }
+            out[len] = (acc >> acc_bits) as u8;
+            len += 1;
+        }
+        acc &= (1 << acc_bits) - 1;
+    }
+    // Characters that encode less than a byte are only valid as the final, zero padded, chunk
+    if acc_bits >= bits || acc != 0 {
+        return Err(Error::InvalidBaseString);
+    }
+    Ok(len)
+}
+
+fn decode_bigint(alphabet: &[u8], input: &[u8], out: &mut [u8]) -> Result<usize, Error> {
+    let base = alphabet.len() as u32;
+    let zeros = input.iter().take_while(|&&c| c == alphabet[0]).count();
+    if out.len() < zeros {
+        return Err(Error::InsufficientBuffer(zeros));
+    }
+    // The bytes are accumulated in little-endian order behind the leading zeros
+    let bytes = &mut out[zeros..];
+    let mut len = 0;
+    for char in &input[zeros..] {
+        let mut carry = position(alphabet, *char)?;
+        for byte in bytes[..len].iter_mut() {
+            carry += u32::from(*byte) * base;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            if len == bytes.len() {
+                return Err(Error::InsufficientBuffer(zeros + len + 1));
+            }
+            bytes[len] = carry as u8;
+            len += 1;
+            carry >>= 8;
+        }
+    }
+    bytes[..len].reverse();
+    for zero in out[..zeros].iter_mut() {
+        *zero = 0;
+    }
+    Ok(zeros + len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASES: [(Base, &str); 11] = [
+        (Base::Base16Lower, "f796573206d616e692021"),
+        (Base::Base16Upper, "F796573206D616E692021"),
+        (Base::Base32Lower, "bpfsxgidnmfxgsibb"),
+        (Base::Base32Upper, "BPFSXGIDNMFXGSIBB"),
+        (Base::Base32PadLower, "cpfsxgidnmfxgsibb"),
+        (Base::Base32PadUpper, "CPFSXGIDNMFXGSIBB"),
+        (Base::Base36Lower, "k2lcpzo5yikidynfl"),
+        (Base::Base36Upper, "K2LCPZO5YIKIDYNFL"),
+        (Base::Base58Btc, "z7paNL19xttacUY"),
+        (Base::Base64Url, "ueWVzIG1hbmkgIQ"),
+        (Base::Base64UrlPad, "UeWVzIG1hbmkgIQ=="),
+    ];
+
+    #[test]
+    fn test_encode() {
+        for (base, expected) in BASES.iter() {
+            let mut out = [0; 64];
+            let len = encode(*base, b"yes mani !", &mut out).unwrap();
+            assert_eq!(&out[..len], expected.as_bytes(), "{:?}", base);
+            assert!(len <= base.max_encoded_len(10), "{:?}", base);
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        for (base, input) in BASES.iter() {
+            let mut out = [0; 64];
+            let (decoded_base, decoded) = decode(input, &mut out).unwrap();
+            assert_eq!(decoded_base, *base);
+            assert_eq!(decoded, b"yes mani !", "{:?}", base);
+        }
+    }
+
+    #[test]
+    fn test_leading_zeros() {
+        for (base, expected) in [
+            (Base::Base36Lower, "k002lcpzo5yikidynfl"),
+            (Base::Base58Btc, "z117paNL19xttacUY"),
+        ]
+        .iter()
+        {
+            let mut out = [0; 64];
+            let len = encode(*base, b"\x00\x00yes mani !", &mut out).unwrap();
+            assert_eq!(&out[..len], expected.as_bytes());
+            let mut decoded = [0; 64];
+            let (_, decoded) = decode(expected, &mut decoded).unwrap();
+            assert_eq!(decoded, b"\x00\x00yes mani !");
+        }
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let mut out = [0; 64];
+        assert!(matches!(
+            decode("", &mut out),
+            Err(Error::InvalidBaseString)
+        ));
+        assert!(matches!(
+            decode("x1234", &mut out),
+            Err(Error::UnsupportedBase('x'))
+        ));
+        assert!(matches!(
+            decode("z0OIl", &mut out),
+            Err(Error::InvalidBaseString)
+        ));
+        assert!(matches!(
+            decode("UeWVzIG1hbmkgIQ=", &mut out),
+            Err(Error::InvalidBaseString)
+        ));
+        assert!(matches!(
+            decode("f796573206d616e69202", &mut out),
+            Err(Error::InvalidBaseString)
+        ));
+        assert!(matches!(
+            decode("f796573206d616e692021", &mut out[..4]),
+            Err(Error::InsufficientBuffer(5))
+        ));
+    }
+}
@@ -1,10 +1,21 @@
-use crate::hasher::{Digest, Size};
+use crate::hasher::{Digest, Hasher, HasherVisitor, Size, StatefulHasher};
+use crate::multibase::{self, Base};
+use crate::multihash_ref::MultihashRef;
 use crate::Error;
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec, vec::Vec};
 use core::convert::TryFrom;
-#[cfg(feature = "std")]
-use core::convert::TryInto;
 use core::fmt::Debug;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::str::FromStr;
+use generic_array::typenum::{U128, U16, U20, U28, U32, U48, U64};
 use generic_array::{ArrayLength, GenericArray};
+use unsigned_varint::{decode as varint_decode, encode as varint_encode};
+
+/// The maximum length of an encoded multihash, a 64-bit varint code, the varint size and a 255 byte
+/// digest.
+pub(crate) const MAX_ENCODED_LEN: usize = 10 + 2 + 255;
 
 /// Trait that implements hashing.
 ///
@@ -19,6 +30,11 @@ pub trait MultihashCode:
 
     /// Calculate the hash of some input data.
     ///
+    /// # Panics
+    ///
+    /// Panics if the code has no hash function, e.g. an unknown code of a derived code table. Code
+    /// tables with such codes should use [`MultihashCode::try_digest`], which errors instead.
+    ///
     /// # Example
     ///
     /// ```
@@ -47,6 +63,173 @@ pub trait MultihashCode:
         S: Size,
         D: Digest<S>,
         Self: From<&'a D>;
+
+    /// Returns the size of the digest the hash function produces.
+    ///
+    /// It's `0` for codes without a hash function.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tiny_multihash::{Code, MultihashCode};
+    ///
+    /// assert_eq!(Code::Sha2_256.size(), 32);
+    /// ```
+    fn size(&self) -> u8;
+
+    /// Calls the visitor with the hasher type of the code.
+    ///
+    /// It errors with [`Error::UnsupportedCode`] if the code has no hash function, e.g. an unknown
+    /// code of a derived code table.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tiny_multihash::{Code, HasherVisitor, MultihashCode, StatefulHasher};
+    ///
+    /// struct DigestLen<'a>(&'a [u8]);
+    ///
+    /// impl HasherVisitor for DigestLen<'_> {
+    ///     type Output = usize;
+    ///
+    ///     fn visit<H: StatefulHasher>(self) -> usize {
+    ///         let mut hasher = H::default();
+    ///         hasher.update(self.0);
+    ///         hasher.finalize().as_ref().len()
+    ///     }
+    /// }
+    ///
+    /// let len = Code::Sha2_512.visit_hasher(DigestLen(b"Hello world!")).unwrap();
+    /// assert_eq!(len, 64);
+    /// ```
+    fn visit_hasher<V: HasherVisitor>(&self, visitor: V) -> Result<V::Output, Error>;
+
+    /// Calculate the hash of some input data.
+    ///
+    /// Unlike [`MultihashCode::digest`] it errors with [`Error::UnsupportedCode`] if the code has
+    /// no hash function.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tiny_multihash::{Code, MultihashCode};
+    ///
+    /// let hash = Code::Sha2_256.try_digest(b"Hello world!").unwrap();
+    /// assert_eq!(hash, Code::Sha2_256.digest(b"Hello world!"));
+    /// ```
+    fn try_digest(&self, input: &[u8]) -> Result<Multihash<Self::AllocSize>, Error> {
+        self.visit_hasher(DigestVisitor {
+            code: (*self).into(),
+            input,
+            _marker: PhantomData,
+        })?
+    }
+
+    /// Calculate the hash of some input data and truncate it to `len` bytes.
+    ///
+    /// It errors with [`Error::InvalidSize`] if `len` is bigger than the size of the digest and with
+    /// [`Error::UnsupportedCode`] if the code has no hash function.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tiny_multihash::{Code, MultihashCode};
+    ///
+    /// let hash = Code::Sha2_256.digest_truncated(b"Hello world!", 20).unwrap();
+    /// assert_eq!(hash.size(), 20);
+    /// ```
+    fn digest_truncated(&self, input: &[u8], len: u8) -> Result<Multihash<Self::AllocSize>, Error> {
+        self.try_digest(input)?.truncate(len)
+    }
+
+    /// Checks the size of a multihash digest against the size of the hash function.
+    ///
+    /// Returns `true` if the digest is truncated and `false` if it has the full size. A digest
+    /// that is bigger than what the hash function produces is corrupt, this errors with
+    /// [`Error::InvalidSize`]. Hash functions with a variable length (like the identity hash)
+    /// report shorter digests as truncated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tiny_multihash::{Code, MultihashCode};
+    ///
+    /// let hash = Code::Sha2_256.digest_truncated(b"Hello world!", 20).unwrap();
+    /// assert_eq!(Code::Sha2_256.is_truncated(hash.size()).unwrap(), true);
+    /// assert!(Code::Sha1.is_truncated(32).is_err());
+    /// ```
+    fn is_truncated(&self, size: u8) -> Result<bool, Error> {
+        match size.cmp(&self.size()) {
+            core::cmp::Ordering::Less => Ok(true),
+            core::cmp::Ordering::Equal => Ok(false),
+            core::cmp::Ordering::Greater => Err(Error::InvalidSize(size.into())),
+        }
+    }
+
+    /// Verifies that a multihash is the hash of some data.
+    ///
+    /// The data is hashed with the hash function of the multihash code, truncated digests are
+    /// compared with the same number of bytes. It errors with [`Error::UnsupportedCode`] if the
+    /// code isn't part of this code table or has no hash function, with [`Error::InvalidSize`] if
+    /// the digest is bigger than what the hash function produces and with
+    /// [`Error::DigestMismatch`] if the digests differ.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tiny_multihash::{Code, MultihashCode};
+    ///
+    /// let hash = Code::Sha2_256.digest_truncated(b"Hello world!", 20).unwrap();
+    /// assert!(Code::verify(&hash, b"Hello world!").is_ok());
+    /// assert!(Code::verify(&hash, b"Hello world?").is_err());
+    /// ```
+    fn verify<S: Size>(mh: &Multihash<S>, data: &[u8]) -> Result<(), Error> {
+        let code = Self::try_from(mh.code()).map_err(|_| Error::UnsupportedCode(mh.code()))?;
+        let hash = code.try_digest(data)?;
+        // Digests that are bigger than the hash function's output are corrupt
+        code.is_truncated(mh.size())?;
+        if digest_matches(code.size(), hash.digest(), mh.digest()) {
+            Ok(())
+        } else {
+            Err(Error::DigestMismatch)
+        }
+    }
+}
+
+/// Looks up the code of a hasher in a code table.
+///
+/// The [`Multihash` derive] implements it for the hasher of every code. Unlike the `From`
+/// implementations for the digest types, it's keyed on the hasher type, hence it's also available
+/// if the `digest` attribute is omitted.
+///
+/// # Example
+///
+/// ```
+/// use tiny_multihash::{Code, FromHasher, Sha2_256};
+///
+/// assert_eq!(<Code as FromHasher<Sha2_256>>::from_hasher(), Code::Sha2_256);
+/// ```
+///
+/// [`Multihash` derive]: crate::derive
+pub trait FromHasher<H: StatefulHasher> {
+    /// Returns the code of the hasher `H`.
+    fn from_hasher() -> Self;
+}
+
+/// Hashes some input with the visited hasher.
+struct DigestVisitor<'a, S> {
+    code: u64,
+    input: &'a [u8],
+    _marker: PhantomData<S>,
+}
+
+impl<S: Size> HasherVisitor for DigestVisitor<'_, S> {
+    type Output = Result<Multihash<S>, Error>;
+
+    fn visit<H: StatefulHasher + 'static>(self) -> Self::Output {
+        let digest = H::digest(self.input);
+        Multihash::wrap(self.code, digest.as_ref())
+    }
 }
 
 /// A Multihash instance that only supports the basic functionality and no hashing.
@@ -70,10 +253,13 @@ pub trait MultihashCode:
 /// assert_eq!(mh.size(), 32);
 /// assert_eq!(mh.digest(), &digest_bytes[2..]);
 /// ```
-#[cfg_attr(feature = "serde-codec", derive(serde::Deserialize))]
-#[cfg_attr(feature = "serde-codec", derive(serde::Serialize))]
-#[cfg_attr(feature = "serde-codec", serde(bound = "S: Size"))]
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+///
+/// With the `serde-codec` feature, multihashes are serialized as base58btc [multibase] string in
+/// human-readable formats and as their canonical bytes otherwise. The struct form of older
+/// versions can still be read with [`serde_compat`](crate::serde_compat).
+///
+/// [multibase]: https://github.com/multiformats/multibase
+#[derive(Clone, Default, Eq)]
 pub struct Multihash<S: Size> {
     /// The code of the Multihash.
     code: u64,
@@ -85,35 +271,180 @@ pub struct Multihash<S: Size> {
 
 impl<S: Size> Copy for Multihash<S> where <S as ArrayLength<u8>>::ArrayType: Copy {}
 
+/// Only the code, the size and the actual digest are compared, the padding of the allocated digest
+/// is ignored. Hence multihashes of different allocation sizes can be compared as well.
+impl<S: Size, S2: Size> PartialEq<Multihash<S2>> for Multihash<S> {
+    fn eq(&self, other: &Multihash<S2>) -> bool {
+        self.code == other.code && self.size == other.size && self.digest() == other.digest()
+    }
+}
+
+/// Implements the infallible conversion into multihashes with a bigger allocation size.
+macro_rules! impl_from_smaller_alloc_size {
+    ($from:ty => $($into:ty),*) => {
+        $(
+            impl From<Multihash<$from>> for Multihash<$into> {
+                fn from(mh: Multihash<$from>) -> Self {
+                    Self::wrap_unchecked(mh.code, mh.digest())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_smaller_alloc_size!(U16 => U20, U28, U32, U48, U64, U128);
+impl_from_smaller_alloc_size!(U20 => U28, U32, U48, U64, U128);
+impl_from_smaller_alloc_size!(U28 => U32, U48, U64, U128);
+impl_from_smaller_alloc_size!(U32 => U48, U64, U128);
+impl_from_smaller_alloc_size!(U48 => U64, U128);
+impl_from_smaller_alloc_size!(U64 => U128);
+
+impl<S: Size> core::hash::Hash for Multihash<S> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.code.hash(state);
+        self.size.hash(state);
+        self.digest().hash(state);
+    }
+}
+
+/// Multihashes are ordered by the lexicographic order of their encoded bytes.
+impl<S: Size> Ord for Multihash<S> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        let mut code_buf = varint_encode::u64_buffer();
+        let mut size_buf = varint_encode::u8_buffer();
+        let mut other_code_buf = varint_encode::u64_buffer();
+        let mut other_size_buf = varint_encode::u8_buffer();
+        let bytes = varint_encode::u64(self.code, &mut code_buf)
+            .iter()
+            .chain(varint_encode::u8(self.size, &mut size_buf))
+            .chain(self.digest());
+        let other_bytes = varint_encode::u64(other.code, &mut other_code_buf)
+            .iter()
+            .chain(varint_encode::u8(other.size, &mut other_size_buf))
+            .chain(other.digest());
+        bytes.cmp(other_bytes)
+    }
+}
+
+impl<S: Size> PartialOrd for Multihash<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Checks at compile-time that a digest of `N` bytes fits into the allocated size `S`.
+struct LiteralFits<S, const N: usize>(PhantomData<S>);
+
+impl<S: Size, const N: usize> LiteralFits<S, N> {
+    const OK: () = assert!(
+        N <= S::USIZE,
+        "the multihash digest is bigger than the allocated size"
+    );
+}
+
 impl<S: Size> Multihash<S> {
     /// Wraps the digest in a multihash.
-    pub fn wrap(code: u64, input_digest: &[u8]) -> Result<Self, Error> {
-        if input_digest.len() > S::to_usize() {
+    ///
+    /// In order to define multihash constants, use the [`multihash!`] macro, which parses a
+    /// multihash literal at compile-time.
+    ///
+    /// [`multihash!`]: crate::derive::multihash
+    pub const fn wrap(code: u64, input_digest: &[u8]) -> Result<Self, Error> {
+        if input_digest.len() > S::USIZE {
             return Err(Error::InvalidSize(input_digest.len() as _));
         }
+        Ok(Self::wrap_unchecked(code, input_digest))
+    }
+
+    /// Wraps the digest of a multihash literal, used by the [`multihash!`] macro.
+    ///
+    /// The size check is a constant, hence it fails to compile if the digest doesn't fit, also
+    /// outside of `const` and `static` items.
+    ///
+    /// [`multihash!`]: crate::derive::multihash
+    #[doc(hidden)]
+    pub const fn __wrap_literal<const N: usize>(code: u64, input_digest: &[u8; N]) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = LiteralFits::<S, N>::OK;
+        Self::wrap_unchecked(code, input_digest)
+    }
+
+    /// Wraps the digest, the caller needs to make sure that it fits into the allocated size.
+    const fn wrap_unchecked(code: u64, input_digest: &[u8]) -> Self {
         let size = input_digest.len();
+        let mut digest = MaybeUninit::<GenericArray<u8, S>>::zeroed();
+        // SAFETY: `GenericArray` has no const constructor. A `GenericArray<u8, S>` is laid out as
+        // `S` contiguous bytes, the zeroed bytes are valid and `size` is at most `S`.
+        unsafe {
+            core::ptr::copy_nonoverlapping(input_digest.as_ptr(), digest.as_mut_ptr().cast(), size);
+            Self {
+                code,
+                size: size as u8,
+                digest: digest.assume_init(),
+            }
+        }
+    }
+
+    /// Truncates the digest to `len` bytes.
+    ///
+    /// It errors with [`Error::InvalidSize`] if `len` is bigger than the size of the digest.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tiny_multihash::{Multihash, U64};
+    ///
+    /// let mh = Multihash::<U64>::wrap(0x12, &[1, 2, 3, 4]).unwrap();
+    /// assert_eq!(mh.truncate(2).unwrap().digest(), &[1, 2]);
+    /// assert!(mh.truncate(5).is_err());
+    /// ```
+    pub fn truncate(&self, len: u8) -> Result<Self, Error> {
+        if len > self.size {
+            return Err(Error::InvalidSize(len.into()));
+        }
         let mut digest = GenericArray::default();
-        digest[..size].copy_from_slice(input_digest);
+        digest[..len as usize].copy_from_slice(&self.digest()[..len as usize]);
         Ok(Self {
-            code,
-            size: size as u8,
+            code: self.code,
+            size: len,
             digest,
         })
     }
 
+    /// Converts the multihash into one with a different allocation size.
+    ///
+    /// It errors with [`Error::InvalidSize`] if the digest doesn't fit into the new allocation
+    /// size. Converting into a bigger allocation size never fails, this is also possible via
+    /// `From`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tiny_multihash::{Multihash, U32, U64};
+    ///
+    /// let mh = Multihash::<U64>::wrap(0x12, &[1, 2, 3]).unwrap();
+    /// let resized = mh.resize::<U32>().unwrap();
+    /// assert_eq!(resized, mh);
+    /// ```
+    pub fn resize<R: Size>(&self) -> Result<Multihash<R>, Error> {
+        Multihash::wrap(self.code, self.digest())
+    }
+
     /// Returns the code of the multihash.
-    pub fn code(&self) -> u64 {
+    pub const fn code(&self) -> u64 {
         self.code
     }
 
     /// Returns the size of the digest.
-    pub fn size(&self) -> u8 {
+    pub const fn size(&self) -> u8 {
         self.size
     }
 
     /// Returns the digest.
-    pub fn digest(&self) -> &[u8] {
-        &self.digest[..self.size as usize]
+    pub const fn digest(&self) -> &[u8] {
+        let bytes = &self.digest as *const GenericArray<u8, S> as *const u8;
+        // SAFETY: a `GenericArray<u8, S>` is laid out as `S` contiguous bytes and `size <= S`.
+        unsafe { core::slice::from_raw_parts(bytes, self.size as usize) }
     }
 
     /// Reads a multihash from a byte stream.
@@ -126,21 +457,41 @@ impl<S: Size> Multihash<S> {
         Ok(Self { code, size, digest })
     }
 
+    /// Reads a multihash from the beginning of a byte slice.
+    ///
+    /// Returns the multihash and the remaining bytes that follow it. This doesn't allocate and is
+    /// available without the `std` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tiny_multihash::{Multihash, U64};
+    ///
+    /// let bytes = [0x00, 0x03, 0x01, 0x02, 0x03, 0xff];
+    /// let (mh, rest) = Multihash::<U64>::from_slice(&bytes).unwrap();
+    /// assert_eq!(mh.digest(), &[0x01, 0x02, 0x03]);
+    /// assert_eq!(rest, &[0xff]);
+    /// ```
+    pub fn from_slice(bytes: &[u8]) -> Result<(Self, &[u8]), Error>
+    where
+        Self: Sized,
+    {
+        let (code, size, digest, rest) = read_multihash_slice(bytes)?;
+        Ok((Self { code, size, digest }, rest))
+    }
+
     /// Parses a multihash from a bytes.
     ///
     /// You need to make sure the passed in bytes have the correct length. The digest length
     /// needs to match the `size` value of the multihash.
-    #[cfg(feature = "std")]
-    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, Error>
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error>
     where
         Self: Sized,
     {
-        let result = Self::read(&mut bytes)?;
+        let (result, rest) = Self::from_slice(bytes)?;
         // There were more bytes supplied than read
-        if !bytes.is_empty() {
-            return Err(Error::InvalidSize(bytes.len().try_into().expect(
-                "Currently the maximum size is 255, therefore always fits into usize",
-            )));
+        if !rest.is_empty() {
+            return Err(Error::InvalidSize(rest.len() as u64));
         }
 
         Ok(result)
@@ -149,92 +500,192 @@ impl<S: Size> Multihash<S> {
     /// Writes a multihash to a byte stream.
     #[cfg(feature = "std")]
     pub fn write<W: std::io::Write>(&self, w: W) -> Result<(), Error> {
-        write_multihash(w, self.code(), self.size(), self.digest())
+        write_multihash(w, self.code(), self.digest())
+    }
+
+    /// Writes a multihash into a byte slice.
+    ///
+    /// Returns the number of bytes written, which equals [`Multihash::encoded_len`]. Errors with
+    /// [`Error::InsufficientBuffer`] if `buf` is too small. This doesn't allocate and is available
+    /// without the `std` feature.
+    pub fn write_to_slice(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        write_multihash_slice(buf, self.code(), self.digest())
+    }
+
+    /// Returns the length in bytes of the encoded multihash (code, size and the digest).
+    pub fn encoded_len(&self) -> usize {
+        encoded_len(self.code, self.digest().len())
     }
 
     /// Returns the bytes of a multihash.
-    #[cfg(feature = "std")]
+    #[cfg(feature = "alloc")]
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = vec![];
-        self.write(&mut bytes)
-            .expect("writing to a vec should never fail");
+        let mut bytes = vec![0; self.encoded_len()];
+        self.write_to_slice(&mut bytes)
+            .expect("the vec is allocated with the encoded length");
         bytes
     }
+
+    /// Encodes the multihash as [multibase] string into `buf`.
+    ///
+    /// Returns the string, which borrows from `buf`. This doesn't allocate and is available
+    /// without the `std` feature. It errors with [`Error::InsufficientBuffer`] if `buf` is smaller
+    /// than `base.max_encoded_len(self.encoded_len())` bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tiny_multihash::{Base, Multihash, U64};
+    ///
+    /// let mh = Multihash::<U64>::wrap(0x12, &[1, 2, 3]).unwrap();
+    /// let mut buf = [0; 16];
+    /// assert_eq!(mh.to_str_of_base(Base::Base16Lower, &mut buf).unwrap(), "f1203010203");
+    /// ```
+    ///
+    /// [multibase]: https://github.com/multiformats/multibase
+    pub fn to_str_of_base<'a>(&self, base: Base, buf: &'a mut [u8]) -> Result<&'a str, Error> {
+        let mut bytes = [0; MAX_ENCODED_LEN];
+        let len = self.write_to_slice(&mut bytes)?;
+        let len = multibase::encode(base, &bytes[..len], buf)?;
+        Ok(core::str::from_utf8(&buf[..len]).expect("multibase strings are ASCII"))
+    }
+
+    /// Returns the multihash as [multibase] string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tiny_multihash::{Base, Multihash, U64};
+    ///
+    /// let mh = Multihash::<U64>::wrap(0x12, &[1, 2, 3]).unwrap();
+    /// assert_eq!(mh.to_string_of_base(Base::Base32Lower), "bcibqcaqd");
+    /// ```
+    ///
+    /// [multibase]: https://github.com/multiformats/multibase
+    #[cfg(feature = "alloc")]
+    pub fn to_string_of_base(&self, base: Base) -> String {
+        let mut buf = vec![0; base.max_encoded_len(self.encoded_len())];
+        let len = self
+            .to_str_of_base(base, &mut buf)
+            .expect("the vec is allocated with the maximum encoded length")
+            .len();
+        buf.truncate(len);
+        String::from_utf8(buf).expect("multibase strings are ASCII")
+    }
+
+    /// Parses a multihash from a [multibase] string of any of the supported [`Base`]s.
+    ///
+    /// This doesn't allocate and is available without the `std` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tiny_multihash::{Multihash, U64};
+    ///
+    /// let mh = Multihash::<U64>::from_str_any_base("zQmaozNR7DZHQK1ZcU9p7QdrshMvXqWK6gpu5rmrkPdT3L4")
+    ///     .unwrap();
+    /// assert_eq!(mh.code(), 0x12);
+    /// assert_eq!(mh.size(), 32);
+    /// ```
+    ///
+    /// [multibase]: https://github.com/multiformats/multibase
+    pub fn from_str_any_base(input: &str) -> Result<Self, Error> {
+        let mut buf = [0; MAX_ENCODED_LEN];
+        let (_base, bytes) = multibase::decode(input, &mut buf).map_err(|err| match err {
+            // The decoded bytes can't be a valid multihash
+            Error::InsufficientBuffer(len) => Error::InvalidSize(len as u64),
+            err => err,
+        })?;
+        Self::from_bytes(bytes)
+    }
 }
 
-#[cfg(feature = "scale-codec")]
-impl parity_scale_codec::Encode for Multihash<crate::U32> {
-    fn encode_to<EncOut: parity_scale_codec::Output>(&self, dest: &mut EncOut) {
-        let mut digest = [0; 32];
-        digest.copy_from_slice(&self.digest);
-        dest.push(&self.code);
-        dest.push(&self.size);
-        dest.push(&digest);
+/// Compares the full digest of a hash function with a possibly truncated expected digest.
+///
+/// Only full size digests can be truncated, shorter ones (e.g. of the identity hash) need to match
+/// exactly.
+pub(crate) fn digest_matches(full_size: u8, digest: &[u8], expected: &[u8]) -> bool {
+    if digest.len() == full_size as usize {
+        digest.get(..expected.len()) == Some(expected)
+    } else {
+        digest == expected
     }
 }
 
-#[cfg(feature = "scale-codec")]
-impl parity_scale_codec::EncodeLike for Multihash<crate::U32> {}
-
-#[cfg(feature = "scale-codec")]
-impl parity_scale_codec::Decode for Multihash<crate::U32> {
-    fn decode<DecIn: parity_scale_codec::Input>(
-        input: &mut DecIn,
-    ) -> Result<Self, parity_scale_codec::Error> {
-        Ok(Multihash {
-            code: parity_scale_codec::Decode::decode(input)?,
-            size: parity_scale_codec::Decode::decode(input)?,
-            digest: {
-                let digest = <[u8; 32]>::decode(input)?;
-                GenericArray::clone_from_slice(&digest)
-            },
-        })
+/// Formats the multihash as `name:hex`, e.g. `sha2-256:b94d…`.
+///
+/// Codes that aren't in the [multicodec table](crate::codes) are printed as hex number, e.g.
+/// `0x3312e7:b94d…`.
+impl<S: Size> Debug for Multihash<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        debug_multihash(self.code, self.digest(), f)
     }
 }
 
-#[cfg(feature = "scale-codec")]
-impl parity_scale_codec::Encode for Multihash<crate::U64> {
-    fn encode_to<EncOut: parity_scale_codec::Output>(&self, dest: &mut EncOut) {
-        let mut digest = [0; 64];
-        digest.copy_from_slice(&self.digest);
-        dest.push(&self.code);
-        dest.push(&self.size);
-        dest.push(&digest);
+/// Writes the `name:hex` representation that is used by the `Debug` implementations.
+pub(crate) fn debug_multihash(
+    code: u64,
+    digest: &[u8],
+    f: &mut core::fmt::Formatter,
+) -> core::fmt::Result {
+    match crate::codes::by_code(code) {
+        Some(entry) => write!(f, "{}:", entry.name)?,
+        None => write!(f, "{:#x}:", code)?,
+    }
+    for byte in digest {
+        write!(f, "{:02x}", byte)?;
     }
+    Ok(())
 }
 
-#[cfg(feature = "scale-codec")]
-impl parity_scale_codec::EncodeLike for Multihash<crate::U64> {}
-
-#[cfg(feature = "scale-codec")]
-impl parity_scale_codec::Decode for Multihash<crate::U64> {
-    fn decode<DecIn: parity_scale_codec::Input>(
-        input: &mut DecIn,
-    ) -> Result<Self, parity_scale_codec::Error> {
-        Ok(Multihash {
-            code: parity_scale_codec::Decode::decode(input)?,
-            size: parity_scale_codec::Decode::decode(input)?,
-            digest: {
-                let digest = <[u8; 64]>::decode(input)?;
-                GenericArray::clone_from_slice(&digest)
-            },
-        })
+/// Formats the multihash as base58btc [multibase] string.
+///
+/// [multibase]: https://github.com/multiformats/multibase
+impl<S: Size> core::fmt::Display for Multihash<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let mut buf = [0; 2 * MAX_ENCODED_LEN + 1];
+        let string = self
+            .to_str_of_base(Base::Base58Btc, &mut buf)
+            .map_err(|_| core::fmt::Error)?;
+        f.write_str(string)
+    }
+}
+
+/// Parses a multihash from a [multibase] string, see [`Multihash::from_str_any_base`].
+///
+/// [multibase]: https://github.com/multiformats/multibase
+impl<S: Size> FromStr for Multihash<S> {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::from_str_any_base(input)
     }
 }
 
+/// Returns the length in bytes of an encoded multihash (code, size and the digest).
+pub(crate) fn encoded_len(code: u64, digest_len: usize) -> usize {
+    let mut code_buf = varint_encode::u64_buffer();
+    let code = varint_encode::u64(code, &mut code_buf);
+
+    let mut size_buf = varint_encode::u64_buffer();
+    let size = varint_encode::u64(digest_len as u64, &mut size_buf);
+
+    code.len() + size.len() + digest_len
+}
+
 /// Writes the multihash to a byte stream.
+///
+/// The size is the length of the digest.
 #[cfg(feature = "std")]
-pub fn write_multihash<W>(mut w: W, code: u64, size: u8, digest: &[u8]) -> Result<(), Error>
+pub fn write_multihash<W>(mut w: W, code: u64, digest: &[u8]) -> Result<(), Error>
 where
     W: std::io::Write,
 {
-    use unsigned_varint::encode as varint_encode;
-
     let mut code_buf = varint_encode::u64_buffer();
     let code = varint_encode::u64(code, &mut code_buf);
 
-    let mut size_buf = varint_encode::u8_buffer();
-    let size = varint_encode::u8(size, &mut size_buf);
+    let mut size_buf = varint_encode::u64_buffer();
+    let size = varint_encode::u64(digest.len() as u64, &mut size_buf);
 
     w.write_all(code)?;
     w.write_all(size)?;
@@ -242,6 +693,28 @@ where
     Ok(())
 }
 
+/// Writes the multihash into a byte slice.
+///
+/// The size is the length of the digest. Returns the number of bytes written.
+pub fn write_multihash_slice(buf: &mut [u8], code: u64, digest: &[u8]) -> Result<usize, Error> {
+    let mut code_buf = varint_encode::u64_buffer();
+    let code = varint_encode::u64(code, &mut code_buf);
+
+    let mut size_buf = varint_encode::u64_buffer();
+    let size = varint_encode::u64(digest.len() as u64, &mut size_buf);
+
+    let len = code.len() + size.len() + digest.len();
+    if buf.len() < len {
+        return Err(Error::InsufficientBuffer(len));
+    }
+    let (code_out, rest) = buf.split_at_mut(code.len());
+    code_out.copy_from_slice(code);
+    let (size_out, rest) = rest.split_at_mut(size.len());
+    size_out.copy_from_slice(size);
+    rest[..digest.len()].copy_from_slice(digest);
+    Ok(len)
+}
+
 /// Reads a multihash from a byte stream that contains a full multihash (code, size and the digest)
 ///
 /// Returns the code, size and the digest. The size is the actual size and not the
@@ -268,6 +741,48 @@ where
     Ok((code, size as u8, digest))
 }
 
+/// Splits a multihash with a digest of at most `max_size` bytes from the beginning of a byte slice.
+///
+/// Returns the code, the digest and the remaining bytes.
+pub(crate) fn split_multihash_slice(
+    bytes: &[u8],
+    max_size: u64,
+) -> Result<(u64, &[u8], &[u8]), Error> {
+    let (code, bytes) = varint_decode::u64(bytes)?;
+    let (size, bytes) = varint_decode::u64(bytes)?;
+
+    if size > max_size {
+        return Err(Error::InvalidSize(size));
+    }
+    if (bytes.len() as u64) < size {
+        return Err(Error::Varint(varint_decode::Error::Insufficient));
+    }
+
+    let (digest, rest) = bytes.split_at(size as usize);
+    Ok((code, digest, rest))
+}
+
+/// Reads a multihash from the beginning of a byte slice (code, size and the digest).
+///
+/// Returns the code, size, the digest and the remaining bytes. The size is the actual size and
+/// not the maximum/allocated size of the digest.
+///
+/// Currently the maximum size for a digest is 255 bytes.
+#[allow(clippy::type_complexity)]
+pub fn read_multihash_slice<S>(bytes: &[u8]) -> Result<(u64, u8, GenericArray<u8, S>, &[u8]), Error>
+where
+    S: Size,
+{
+    let (mh, rest) = MultihashRef::from_slice(bytes)?;
+    if mh.size() as usize > S::USIZE {
+        return Err(Error::InvalidSize(mh.size().into()));
+    }
+
+    let mut digest = GenericArray::default();
+    digest[..mh.digest().len()].copy_from_slice(mh.digest());
+    Ok((mh.code(), mh.size(), digest, rest))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -278,10 +793,131 @@ mod tests {
         let hash = Code::Sha2_256.digest(b"hello world");
         let mut buf = [0u8; 35];
         hash.write(&mut buf[..]).unwrap();
-        let hash2 = Multihash::read(&buf[..]).unwrap();
+        let hash2 = Multihash::<crate::U64>::read(&buf[..]).unwrap();
         assert_eq!(hash, hash2);
     }
 
+    #[test]
+    fn roundtrip_slice() {
+        let hash = Code::Sha2_256.digest(b"hello world");
+        let mut buf = [0u8; 36];
+        let written = hash.write_to_slice(&mut buf).unwrap();
+        assert_eq!(written, 34);
+        assert_eq!(written, hash.encoded_len());
+        let (hash2, rest) = Multihash::<crate::U64>::from_slice(&buf).unwrap();
+        assert_eq!(hash, hash2);
+        assert_eq!(rest, &[0, 0]);
+    }
+
+    #[test]
+    fn write_to_slice_insufficient_buffer() {
+        let hash = Code::Sha2_256.digest(b"hello world");
+        let mut buf = [0u8; 33];
+        assert!(matches!(
+            hash.write_to_slice(&mut buf),
+            Err(Error::InsufficientBuffer(34))
+        ));
+    }
+
+    #[test]
+    fn multibase_roundtrip() {
+        let hash = Code::Sha2_256.digest(b"hello world");
+        for base in [
+            Base::Base16Lower,
+            Base::Base16Upper,
+            Base::Base32Lower,
+            Base::Base32Upper,
+            Base::Base32PadLower,
+            Base::Base32PadUpper,
+            Base::Base36Lower,
+            Base::Base36Upper,
+            Base::Base58Btc,
+            Base::Base64Url,
+            Base::Base64UrlPad,
+        ]
+        .iter()
+        {
+            let string = hash.to_string_of_base(*base);
+            assert!(string.starts_with(base.code()));
+            let mut buf = [0; 128];
+            assert_eq!(hash.to_str_of_base(*base, &mut buf).unwrap(), string);
+            assert_eq!(
+                Multihash::<crate::U64>::from_str_any_base(&string).unwrap(),
+                hash
+            );
+        }
+    }
+
+    #[test]
+    fn eq_ignores_padding() {
+        let hash = Multihash::<crate::U64>::wrap(0x12, &[1, 2, 3]).unwrap();
+        let mut padded = hash;
+        padded.digest[10] = 0xff;
+        assert_eq!(hash, padded);
+
+        let mut set = std::collections::HashSet::new();
+        set.insert(hash);
+        assert!(set.contains(&padded));
+    }
+
+    #[test]
+    fn ord_matches_encoded_bytes() {
+        let hashes = [
+            Multihash::<crate::U64>::wrap(0x81, &[0]).unwrap(),
+            Multihash::wrap(0x100, &[0]).unwrap(),
+            Multihash::wrap(0x12, &[1, 2]).unwrap(),
+            Multihash::wrap(0x12, &[1]).unwrap(),
+            Multihash::wrap(0x12, &[2]).unwrap(),
+            Multihash::wrap(0x7f, &[]).unwrap(),
+        ];
+        let sorted: std::collections::BTreeSet<_> = hashes.iter().copied().collect();
+        let mut encoded: Vec<_> = hashes.iter().map(Multihash::to_bytes).collect();
+        encoded.sort();
+        let sorted_encoded: Vec<_> = sorted.iter().map(Multihash::to_bytes).collect();
+        assert_eq!(sorted_encoded, encoded);
+    }
+
+    #[test]
+    fn resize() {
+        let hash = Code::Sha2_256.digest(b"hello world");
+        let smaller = hash.resize::<crate::U32>().unwrap();
+        assert_eq!(smaller, hash);
+        assert_eq!(hash, smaller);
+        assert_eq!(smaller.digest(), hash.digest());
+        assert!(matches!(
+            hash.resize::<crate::U20>(),
+            Err(Error::InvalidSize(32))
+        ));
+
+        let bigger: Multihash<crate::U128> = hash.into();
+        assert_eq!(bigger, hash);
+        let widened: Multihash<crate::U64> = smaller.into();
+        assert_eq!(widened, hash);
+        assert_ne!(smaller, Code::Sha2_256.digest(b"hello"));
+    }
+
+    #[test]
+    fn debug() {
+        let hash = Code::Sha2_256.digest(b"hello world");
+        assert_eq!(
+            format!("{:?}", hash),
+            "sha2-256:b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        );
+        let unknown = Multihash::<crate::U64>::wrap(0x3312e7, &[0xab, 0xcd]).unwrap();
+        assert_eq!(format!("{:?}", unknown), "0x3312e7:abcd");
+    }
+
+    #[test]
+    fn display_from_str() {
+        let hash = Code::Sha2_256.digest(b"hello world");
+        let string = hash.to_string();
+        assert_eq!(string, "zQmaozNR7DZHQK1ZcU9p7QdrshMvXqWK6gpu5rmrkPdT3L4");
+        assert_eq!(string.parse::<Multihash<crate::U64>>().unwrap(), hash);
+        assert!("QmaozNR7DZHQK1ZcU9p7QdrshMvXqWK6gpu5rmrkPdT3L4"
+            .parse::<Multihash<crate::U64>>()
+            .is_err());
+    }
+
     #[test]
     #[cfg(feature = "scale-codec")]
     fn test_scale() {
@@ -298,7 +934,7 @@ mod tests {
     fn test_serde() {
         let mh = Multihash::<crate::U32>::default();
         let bytes = serde_json::to_string(&mh).unwrap();
-        let mh2 = serde_json::from_str(&bytes).unwrap();
+        let mh2: Multihash<crate::U32> = serde_json::from_str(&bytes).unwrap();
         assert_eq!(mh, mh2);
     }
 }
@@ -0,0 +1,277 @@
+use crate::hasher::Size;
+use crate::multibase::{self, Base};
+use crate::multihash::{
+    debug_multihash, encoded_len, split_multihash_slice, write_multihash_slice, Multihash,
+};
+use crate::multihash_ref::MultihashRef;
+use crate::Error;
+use alloc::{string::String, vec, vec::Vec};
+use core::convert::TryFrom;
+use core::fmt::Debug;
+
+/// A heap-allocated multihash without the 255 byte limit of the digest.
+///
+/// The multihash format encodes the size of the digest as varint, hence digests of identity
+/// hashes that inline data, or of hash functions with an extendable output, can be of any length.
+/// When parsing, the size is checked against a cap, so that untrusted input can't request huge
+/// allocations. It's [`MultihashBuf::DEFAULT_MAX_SIZE`], other caps can be set with the
+/// `*_with_max_size` methods.
+///
+/// This type is only available with the `alloc` feature.
+///
+/// # Example
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use tiny_multihash::{Multihash, MultihashBuf, U64};
+///
+/// let mh = MultihashBuf::wrap(0x00, vec![7; 300]);
+/// let bytes = mh.to_bytes();
+/// assert_eq!(MultihashBuf::from_bytes(&bytes).unwrap(), mh);
+/// assert!(Multihash::<U64>::try_from(mh).is_err());
+/// ```
+#[derive(Clone, Default, Eq, Hash, PartialEq)]
+pub struct MultihashBuf {
+    /// The code of the Multihash.
+    code: u64,
+    /// The digest.
+    digest: Vec<u8>,
+}
+
+impl MultihashBuf {
+    /// The maximum size of a digest that is accepted when parsing a multihash, 1 MiB.
+    pub const DEFAULT_MAX_SIZE: usize = 1024 * 1024;
+
+    /// Wraps the digest in a multihash.
+    pub fn wrap(code: u64, digest: Vec<u8>) -> Self {
+        Self { code, digest }
+    }
+
+    /// Returns the code of the multihash.
+    pub fn code(&self) -> u64 {
+        self.code
+    }
+
+    /// Returns the size of the digest.
+    pub fn size(&self) -> usize {
+        self.digest.len()
+    }
+
+    /// Returns the digest.
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
+
+    /// Returns the code and the digest.
+    pub fn into_inner(self) -> (u64, Vec<u8>) {
+        (self.code, self.digest)
+    }
+
+    /// Reads a multihash from a byte stream.
+    #[cfg(feature = "std")]
+    pub fn read<R: std::io::Read>(r: R) -> Result<Self, Error> {
+        Self::read_with_max_size(r, Self::DEFAULT_MAX_SIZE)
+    }
+
+    /// Reads a multihash with a digest of at most `max_size` bytes from a byte stream.
+    #[cfg(feature = "std")]
+    pub fn read_with_max_size<R: std::io::Read>(mut r: R, max_size: usize) -> Result<Self, Error> {
+        use unsigned_varint::io::read_u64;
+
+        let code = read_u64(&mut r)?;
+        let size = read_u64(&mut r)?;
+
+        if size > max_size as u64 {
+            return Err(Error::InvalidSize(size));
+        }
+
+        let mut digest = vec![0; size as usize];
+        r.read_exact(&mut digest)?;
+        Ok(Self { code, digest })
+    }
+
+    /// Reads a multihash from the beginning of a byte slice.
+    ///
+    /// Returns the multihash and the remaining bytes that follow it.
+    pub fn from_slice(bytes: &[u8]) -> Result<(Self, &[u8]), Error> {
+        Self::from_slice_with_max_size(bytes, Self::DEFAULT_MAX_SIZE)
+    }
+
+    /// Reads a multihash with a digest of at most `max_size` bytes from the beginning of a byte
+    /// slice.
+    ///
+    /// Returns the multihash and the remaining bytes that follow it.
+    pub fn from_slice_with_max_size(bytes: &[u8], max_size: usize) -> Result<(Self, &[u8]), Error> {
+        let (code, digest, rest) = split_multihash_slice(bytes, max_size as u64)?;
+        Ok((Self::wrap(code, digest.to_vec()), rest))
+    }
+
+    /// Parses a multihash from a bytes.
+    ///
+    /// You need to make sure the passed in bytes have the correct length. The digest length
+    /// needs to match the `size` value of the multihash.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Self::from_bytes_with_max_size(bytes, Self::DEFAULT_MAX_SIZE)
+    }
+
+    /// Parses a multihash with a digest of at most `max_size` bytes from a bytes.
+    pub fn from_bytes_with_max_size(bytes: &[u8], max_size: usize) -> Result<Self, Error> {
+        let (result, rest) = Self::from_slice_with_max_size(bytes, max_size)?;
+        // There were more bytes supplied than read
+        if !rest.is_empty() {
+            return Err(Error::InvalidSize(rest.len() as u64));
+        }
+
+        Ok(result)
+    }
+
+    /// Writes a multihash to a byte stream.
+    #[cfg(feature = "std")]
+    pub fn write<W: std::io::Write>(&self, w: W) -> Result<(), Error> {
+        crate::multihash::write_multihash(w, self.code, &self.digest)
+    }
+
+    /// Writes a multihash into a byte slice.
+    ///
+    /// Returns the number of bytes written, which equals [`MultihashBuf::encoded_len`]. Errors
+    /// with [`Error::InsufficientBuffer`] if `buf` is too small.
+    pub fn write_to_slice(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        write_multihash_slice(buf, self.code, &self.digest)
+    }
+
+    /// Returns the length in bytes of the encoded multihash (code, size and the digest).
+    pub fn encoded_len(&self) -> usize {
+        encoded_len(self.code, self.digest.len())
+    }
+
+    /// Returns the bytes of a multihash.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0; self.encoded_len()];
+        self.write_to_slice(&mut bytes)
+            .expect("the vec is allocated with the encoded length");
+        bytes
+    }
+
+    /// Returns the multihash as [multibase] string.
+    ///
+    /// [multibase]: https://github.com/multiformats/multibase
+    pub fn to_string_of_base(&self, base: Base) -> String {
+        let bytes = self.to_bytes();
+        let mut buf = vec![0; base.max_encoded_len(bytes.len())];
+        let len = multibase::encode(base, &bytes, &mut buf)
+            .expect("the vec is allocated with the maximum encoded length");
+        buf.truncate(len);
+        String::from_utf8(buf).expect("multibase strings are ASCII")
+    }
+
+    /// Parses a multihash from a [multibase] string of any of the supported [`Base`]s.
+    ///
+    /// [multibase]: https://github.com/multiformats/multibase
+    pub fn from_str_any_base(input: &str) -> Result<Self, Error> {
+        // The decoded bytes are never longer than the encoded string
+        let mut buf = vec![0; input.len()];
+        let (_base, bytes) = multibase::decode(input, &mut buf)?;
+        Self::from_bytes(bytes)
+    }
+}
+
+impl<S: Size> From<Multihash<S>> for MultihashBuf {
+    fn from(mh: Multihash<S>) -> Self {
+        Self::wrap(mh.code(), mh.digest().to_vec())
+    }
+}
+
+impl From<MultihashRef<'_>> for MultihashBuf {
+    fn from(mh: MultihashRef<'_>) -> Self {
+        Self::wrap(mh.code(), mh.digest().to_vec())
+    }
+}
+
+/// Errors with [`Error::InvalidSize`] if the digest doesn't fit into `S`.
+impl<S: Size> TryFrom<MultihashBuf> for Multihash<S> {
+    type Error = Error;
+
+    fn try_from(mh: MultihashBuf) -> Result<Self, Self::Error> {
+        Self::wrap(mh.code, &mh.digest)
+    }
+}
+
+impl<S: Size> PartialEq<Multihash<S>> for MultihashBuf {
+    fn eq(&self, other: &Multihash<S>) -> bool {
+        self.code == other.code() && self.digest == other.digest()
+    }
+}
+
+impl<S: Size> PartialEq<MultihashBuf> for Multihash<S> {
+    fn eq(&self, other: &MultihashBuf) -> bool {
+        other == self
+    }
+}
+
+/// Formats the multihash as `name:hex`, like [`Multihash`] does.
+impl Debug for MultihashBuf {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        debug_multihash(self.code, &self.digest, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Code, MultihashCode, U32, U64};
+
+    #[test]
+    fn test_roundtrip_big_digest() {
+        let mh = MultihashBuf::wrap(0x00, vec![7; 1000]);
+        let bytes = mh.to_bytes();
+        // Two bytes for the varint size
+        assert_eq!(bytes.len(), 1 + 2 + 1000);
+        assert_eq!(mh.encoded_len(), bytes.len());
+
+        let (mh2, rest) = MultihashBuf::from_slice(&bytes).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(mh2, mh);
+
+        let mut written = Vec::new();
+        mh.write(&mut written).unwrap();
+        assert_eq!(written, bytes);
+        assert_eq!(MultihashBuf::read(&written[..]).unwrap(), mh);
+
+        let string = mh.to_string_of_base(Base::Base58Btc);
+        assert_eq!(MultihashBuf::from_str_any_base(&string).unwrap(), mh);
+    }
+
+    #[test]
+    fn test_max_size() {
+        let bytes = MultihashBuf::wrap(0x00, vec![7; 1000]).to_bytes();
+        assert!(matches!(
+            MultihashBuf::from_bytes_with_max_size(&bytes, 999),
+            Err(Error::InvalidSize(1000))
+        ));
+        assert!(matches!(
+            MultihashBuf::read_with_max_size(&bytes[..], 999),
+            Err(Error::InvalidSize(1000))
+        ));
+        assert!(MultihashBuf::from_bytes_with_max_size(&bytes, 1000).is_ok());
+        assert!(MultihashBuf::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_conversions() {
+        let hash = Code::Sha2_256.digest(b"hello world");
+        let mh = MultihashBuf::from(hash);
+        assert_eq!(mh, hash);
+        assert_eq!(hash, mh);
+        assert_eq!(mh.to_bytes(), hash.to_bytes());
+        assert_eq!(format!("{:?}", mh), format!("{:?}", hash));
+        assert_eq!(MultihashBuf::from(MultihashRef::from(&hash)), mh);
+        assert_eq!(Multihash::<U64>::try_from(mh.clone()).unwrap(), hash);
+        assert_eq!(Multihash::<U32>::try_from(mh).unwrap(), hash);
+
+        let big = MultihashBuf::wrap(0x00, vec![7; 33]);
+        assert!(matches!(
+            Multihash::<U32>::try_from(big),
+            Err(Error::InvalidSize(33))
+        ));
+    }
+}
@@ -8,70 +8,72 @@ use tiny_multihash_derive::Multihash;
 /// [`Multihash` derive]: crate::derive
 #[derive(Copy, Clone, Debug, Eq, Multihash, PartialEq)]
 #[mh(alloc_size = crate::U64)]
+#[cfg_attr(feature = "serde-codec", mh(serde))]
+#[cfg_attr(feature = "scale-codec", mh(scale))]
 pub enum Code {
     /// SHA-1 (20-byte hash size)
     #[cfg(feature = "sha1")]
-    #[mh(code = 0x11, hasher = crate::Sha1, digest = crate::Sha1Digest<crate::U20>)]
+    #[mh(code = crate::codes::SHA1, name = "sha1", hasher = crate::Sha1)]
     Sha1,
     /// SHA-256 (32-byte hash size)
     #[cfg(feature = "sha2")]
-    #[mh(code = 0x12, hasher = crate::Sha2_256, digest = crate::Sha2Digest<crate::U32>)]
+    #[mh(code = crate::codes::SHA2_256, name = "sha2-256", hasher = crate::Sha2_256)]
     Sha2_256,
     /// SHA-512 (64-byte hash size)
     #[cfg(feature = "sha2")]
-    #[mh(code = 0x13, hasher = crate::Sha2_512, digest = crate::Sha2Digest<crate::U64>)]
+    #[mh(code = crate::codes::SHA2_512, name = "sha2-512", hasher = crate::Sha2_512)]
     Sha2_512,
     /// SHA3-224 (28-byte hash size)
     #[cfg(feature = "sha3")]
-    #[mh(code = 0x17, hasher = crate::Sha3_224, digest = crate::Sha3Digest<crate::U28>)]
+    #[mh(code = crate::codes::SHA3_224, name = "sha3-224", hasher = crate::Sha3_224)]
     Sha3_224,
     /// SHA3-256 (32-byte hash size)
     #[cfg(feature = "sha3")]
-    #[mh(code = 0x16, hasher = crate::Sha3_256, digest = crate::Sha3Digest<crate::U32>)]
+    #[mh(code = crate::codes::SHA3_256, name = "sha3-256", hasher = crate::Sha3_256)]
     Sha3_256,
     /// SHA3-384 (48-byte hash size)
     #[cfg(feature = "sha3")]
-    #[mh(code = 0x15, hasher = crate::Sha3_384, digest = crate::Sha3Digest<crate::U48>)]
+    #[mh(code = crate::codes::SHA3_384, name = "sha3-384", hasher = crate::Sha3_384)]
     Sha3_384,
     /// SHA3-512 (64-byte hash size)
     #[cfg(feature = "sha3")]
-    #[mh(code = 0x14, hasher = crate::Sha3_512, digest = crate::Sha3Digest<crate::U64>)]
+    #[mh(code = crate::codes::SHA3_512, name = "sha3-512", hasher = crate::Sha3_512)]
     Sha3_512,
     /// Keccak-224 (28-byte hash size)
     #[cfg(feature = "sha3")]
-    #[mh(code = 0x1a, hasher = crate::Keccak224, digest = crate::KeccakDigest<crate::U28>)]
+    #[mh(code = crate::codes::KECCAK_224, name = "keccak-224", hasher = crate::Keccak224)]
     Keccak224,
     /// Keccak-256 (32-byte hash size)
     #[cfg(feature = "sha3")]
-    #[mh(code = 0x1b, hasher = crate::Keccak256, digest = crate::KeccakDigest<crate::U32>)]
+    #[mh(code = crate::codes::KECCAK_256, name = "keccak-256", hasher = crate::Keccak256)]
     Keccak256,
     /// Keccak-384 (48-byte hash size)
     #[cfg(feature = "sha3")]
-    #[mh(code = 0x1c, hasher = crate::Keccak384, digest = crate::KeccakDigest<crate::U48>)]
+    #[mh(code = crate::codes::KECCAK_384, name = "keccak-384", hasher = crate::Keccak384)]
     Keccak384,
     /// Keccak-512 (64-byte hash size)
     #[cfg(feature = "sha3")]
-    #[mh(code = 0x1d, hasher = crate::Keccak512, digest = crate::KeccakDigest<crate::U64>)]
+    #[mh(code = crate::codes::KECCAK_512, name = "keccak-512", hasher = crate::Keccak512)]
     Keccak512,
     /// BLAKE2b-256 (32-byte hash size)
     #[cfg(feature = "blake2b")]
-    #[mh(code = 0xb220, hasher = crate::Blake2b256, digest = crate::Blake2bDigest<crate::U32>)]
+    #[mh(code = crate::codes::BLAKE2B_256, name = "blake2b-256", hasher = crate::Blake2b256)]
     Blake2b256,
     /// BLAKE2b-512 (64-byte hash size)
     #[cfg(feature = "blake2b")]
-    #[mh(code = 0xb240, hasher = crate::Blake2b512, digest = crate::Blake2bDigest<crate::U64>)]
+    #[mh(code = crate::codes::BLAKE2B_512, name = "blake2b-512", hasher = crate::Blake2b512)]
     Blake2b512,
     /// BLAKE2s-128 (16-byte hash size)
     #[cfg(feature = "blake2s")]
-    #[mh(code = 0xb250, hasher = crate::Blake2s128, digest = crate::Blake2sDigest<crate::U16>)]
+    #[mh(code = crate::codes::BLAKE2S_128, name = "blake2s-128", hasher = crate::Blake2s128)]
     Blake2s128,
     /// BLAKE2s-256 (32-byte hash size)
     #[cfg(feature = "blake2s")]
-    #[mh(code = 0xb260, hasher = crate::Blake2s256, digest = crate::Blake2sDigest<crate::U32>)]
+    #[mh(code = crate::codes::BLAKE2S_256, name = "blake2s-256", hasher = crate::Blake2s256)]
     Blake2s256,
     /// BLAKE3-256 (32-byte hash size)
     #[cfg(feature = "blake3")]
-    #[mh(code = 0x1e, hasher = crate::Blake3_256, digest = crate::Blake3Digest<crate::U32>)]
+    #[mh(code = crate::codes::BLAKE3, name = "blake3", hasher = crate::Blake3_256)]
     Blake3_256,
 }
 
@@ -89,7 +91,7 @@ mod tests {
         let hash2 = Code::Sha3_256.digest(b"hello world");
         assert_eq!(hash.code(), u64::from(Code::Sha3_256));
         assert_eq!(hash.size(), 32);
-        assert_eq!(hash.digest(), &digest.as_ref()[..]);
+        assert_eq!(hash.digest(), digest.as_ref());
         assert_eq!(hash, hash2);
     }
 
@@ -100,7 +102,7 @@ mod tests {
         let hash2 = Code::Sha3_512.digest(b"hello world");
         assert_eq!(hash.code(), u64::from(Code::Sha3_512));
         assert_eq!(hash.size(), 64);
-        assert_eq!(hash.digest(), &digest.as_ref()[..]);
+        assert_eq!(hash.digest(), digest.as_ref());
         assert_eq!(hash, hash2);
     }
 }
@@ -0,0 +1,156 @@
+use crate::hasher::Size;
+use crate::multihash::{debug_multihash, split_multihash_slice, Multihash};
+use crate::Error;
+use core::fmt::Debug;
+
+/// A multihash that borrows its digest from a byte slice.
+///
+/// It is the zero-copy counterpart of [`Multihash`], useful for inspecting encoded multihashes
+/// (e.g. keys of an index) without copying the digest into a fixed-size allocation. Use
+/// [`MultihashRef::to_owned`] to get an owned [`Multihash`].
+///
+/// # Example
+///
+/// ```
+/// use tiny_multihash::{Code, MultihashCode, MultihashRef, U64};
+///
+/// let hash = Code::Sha2_256.digest(b"hello world");
+/// let bytes = hash.to_bytes();
+/// let mh = MultihashRef::from_bytes(&bytes).unwrap();
+/// assert_eq!(mh.code(), u64::from(Code::Sha2_256));
+/// assert_eq!(mh.digest(), hash.digest());
+/// assert_eq!(mh, hash);
+/// assert_eq!(mh.to_owned::<U64>().unwrap(), hash);
+/// ```
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub struct MultihashRef<'a> {
+    /// The code of the Multihash.
+    code: u64,
+    /// The digest.
+    digest: &'a [u8],
+}
+
+impl<'a> MultihashRef<'a> {
+    /// Wraps the digest in a borrowed multihash.
+    pub fn wrap(code: u64, input_digest: &'a [u8]) -> Result<Self, Error> {
+        if input_digest.len() > u8::MAX as usize {
+            return Err(Error::InvalidSize(input_digest.len() as _));
+        }
+        Ok(Self {
+            code,
+            digest: input_digest,
+        })
+    }
+
+    /// Returns the code of the multihash.
+    pub const fn code(&self) -> u64 {
+        self.code
+    }
+
+    /// Returns the size of the digest.
+    pub const fn size(&self) -> u8 {
+        self.digest.len() as u8
+    }
+
+    /// Returns the digest.
+    pub const fn digest(&self) -> &'a [u8] {
+        self.digest
+    }
+
+    /// Parses a multihash from the beginning of a byte slice without copying the digest.
+    ///
+    /// Returns the multihash and the remaining bytes.
+    pub fn from_slice(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+        let (code, digest, rest) = split_multihash_slice(bytes, u8::MAX.into())?;
+        Ok((Self { code, digest }, rest))
+    }
+
+    /// Parses a multihash from a byte slice without copying the digest.
+    ///
+    /// It errors if there are trailing bytes after the multihash.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        let (mh, rest) = Self::from_slice(bytes)?;
+        // There were more bytes supplied than read
+        if !rest.is_empty() {
+            return Err(Error::InvalidSize(rest.len() as u64));
+        }
+        Ok(mh)
+    }
+
+    /// Copies the digest into an owned [`Multihash`] with the allocation size `S`.
+    ///
+    /// It errors if the digest doesn't fit into `S`.
+    pub fn to_owned<S: Size>(&self) -> Result<Multihash<S>, Error> {
+        Multihash::wrap(self.code, self.digest)
+    }
+}
+
+impl<'a, S: Size> From<&'a Multihash<S>> for MultihashRef<'a> {
+    fn from(mh: &'a Multihash<S>) -> Self {
+        Self {
+            code: mh.code(),
+            digest: mh.digest(),
+        }
+    }
+}
+
+impl<S: Size> PartialEq<Multihash<S>> for MultihashRef<'_> {
+    fn eq(&self, other: &Multihash<S>) -> bool {
+        self.code == other.code() && self.digest == other.digest()
+    }
+}
+
+impl<S: Size> PartialEq<MultihashRef<'_>> for Multihash<S> {
+    fn eq(&self, other: &MultihashRef<'_>) -> bool {
+        other == self
+    }
+}
+
+/// Formats the multihash as `name:hex`, like [`Multihash`] does.
+impl Debug for MultihashRef<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        debug_multihash(self.code, self.digest, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Code, MultihashCode, U32, U64};
+
+    #[test]
+    fn test_from_slice() {
+        let hash = Code::Sha2_256.digest(b"hello world");
+        let mut bytes = hash.to_bytes();
+        bytes.extend_from_slice(b"rest");
+
+        let (mh, rest) = MultihashRef::from_slice(&bytes).unwrap();
+        assert_eq!(rest, b"rest");
+        assert_eq!(mh.code(), hash.code());
+        assert_eq!(mh.size(), hash.size());
+        assert_eq!(mh.digest(), hash.digest());
+        assert!(MultihashRef::from_bytes(&bytes).is_err());
+        assert!(MultihashRef::from_slice(&bytes[..10]).is_err());
+    }
+
+    #[test]
+    fn test_eq_owned() {
+        let hash = Code::Sha2_256.digest(b"hello world");
+        let bytes = hash.to_bytes();
+        let mh = MultihashRef::from_bytes(&bytes).unwrap();
+        assert_eq!(mh, hash);
+        assert_eq!(hash, mh);
+        assert_eq!(MultihashRef::from(&hash), mh);
+        assert_ne!(mh, Code::Sha2_256.digest(b"hello"));
+        assert_eq!(format!("{:?}", mh), format!("{:?}", hash));
+    }
+
+    #[test]
+    fn test_to_owned() {
+        let digest = [1; 48];
+        let mh = MultihashRef::wrap(0x14, &digest).unwrap();
+        assert_eq!(mh.to_owned::<U64>().unwrap().digest(), &digest[..]);
+        assert!(matches!(mh.to_owned::<U32>(), Err(Error::InvalidSize(48))));
+        assert!(MultihashRef::wrap(0x14, &[0; 256]).is_err());
+    }
+}
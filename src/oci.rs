@@ -0,0 +1,208 @@
+//! Conversion between multihashes and [OCI digest strings] of the form `algorithm:encoded`.
+//!
+//! [OCI digest strings]: https://github.com/opencontainers/image-spec/blob/master/descriptor.md#digests
+#[cfg(feature = "alloc")]
+use crate::hasher::Size;
+use crate::multihash::{Multihash, MultihashCode};
+use crate::{codes, Error};
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+/// A digest algorithm that is registered in the OCI image specification.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum OciAlgorithm {
+    /// SHA-256, `sha256:` followed by 64 lowercase hex characters.
+    Sha256,
+    /// SHA-512, `sha512:` followed by 128 lowercase hex characters.
+    Sha512,
+}
+
+impl OciAlgorithm {
+    /// Returns the OCI name of the algorithm.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha512 => "sha512",
+        }
+    }
+
+    /// Returns the multihash code of the algorithm.
+    pub fn code(&self) -> u64 {
+        match self {
+            Self::Sha256 => codes::SHA2_256,
+            Self::Sha512 => codes::SHA2_512,
+        }
+    }
+
+    /// Returns the size of the digest in bytes.
+    pub fn size(&self) -> u8 {
+        match self {
+            Self::Sha256 => 32,
+            Self::Sha512 => 64,
+        }
+    }
+
+    /// Returns the algorithm for an OCI name.
+    pub fn from_name(name: &str) -> Result<Self, Error> {
+        match name {
+            "sha256" => Ok(Self::Sha256),
+            "sha512" => Ok(Self::Sha512),
+            _ => Err(Error::InvalidOciDigest),
+        }
+    }
+
+    /// Returns the algorithm for a multihash code.
+    pub fn from_code(code: u64) -> Result<Self, Error> {
+        match code {
+            codes::SHA2_256 => Ok(Self::Sha256),
+            codes::SHA2_512 => Ok(Self::Sha512),
+            _ => Err(Error::UnsupportedCode(code)),
+        }
+    }
+}
+
+/// Returns the value of a lowercase hex character.
+fn hex_value(char: u8) -> Result<u8, Error> {
+    match char {
+        b'0'..=b'9' => Ok(char - b'0'),
+        b'a'..=b'f' => Ok(char - b'a' + 10),
+        _ => Err(Error::InvalidOciDigest),
+    }
+}
+
+/// Conversion between the multihashes of a code table and OCI digest strings.
+///
+/// It is implemented for all code tables. Only the algorithms registered in the OCI image
+/// specification are supported and their codes need to be part of the code table.
+///
+/// # Example
+///
+/// ```
+/// use tiny_multihash::{Code, MultihashCode, OciDigest};
+///
+/// let oci = "sha256:b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+/// let mh = Code::from_oci_digest(oci).unwrap();
+/// assert_eq!(mh, Code::Sha2_256.digest(b"hello world"));
+/// assert_eq!(Code::to_oci_digest(&mh).unwrap(), oci);
+/// ```
+pub trait OciDigest: MultihashCode {
+    /// Parses an OCI digest string, e.g. `sha256:4b2c…`.
+    ///
+    /// The validation rules of the OCI image specification apply, i.e. the digest must be
+    /// lowercase hex of the exact length. It errors with [`Error::UnsupportedCode`] if the code of
+    /// the algorithm isn't part of the code table.
+    fn from_oci_digest(input: &str) -> Result<Multihash<Self::AllocSize>, Error>;
+
+    /// Returns the OCI digest string of a multihash, e.g. `sha256:4b2c…`.
+    ///
+    /// It errors if the code isn't part of the code table or isn't registered as OCI algorithm,
+    /// or if the size of the digest doesn't match the algorithm (e.g. because it was truncated).
+    #[cfg(feature = "alloc")]
+    fn to_oci_digest<S: Size>(mh: &Multihash<S>) -> Result<String, Error>;
+}
+
+impl<C: MultihashCode> OciDigest for C {
+    fn from_oci_digest(input: &str) -> Result<Multihash<Self::AllocSize>, Error> {
+        let mut parts = input.splitn(2, ':');
+        let algorithm = OciAlgorithm::from_name(parts.next().unwrap_or_default())?;
+        let code = Self::try_from(algorithm.code())
+            .map_err(|_| Error::UnsupportedCode(algorithm.code()))?;
+        let encoded = parts.next().ok_or(Error::InvalidOciDigest)?.as_bytes();
+        if encoded.len() != 2 * algorithm.size() as usize {
+            return Err(Error::InvalidOciDigest);
+        }
+
+        let mut digest = [0; 64];
+        for (byte, chars) in digest.iter_mut().zip(encoded.chunks(2)) {
+            *byte = hex_value(chars[0])? << 4 | hex_value(chars[1])?;
+        }
+        Multihash::wrap(code.into(), &digest[..algorithm.size() as usize])
+    }
+
+    #[cfg(feature = "alloc")]
+    fn to_oci_digest<S: Size>(mh: &Multihash<S>) -> Result<String, Error> {
+        use core::fmt::Write;
+
+        let code = Self::try_from(mh.code()).map_err(|_| Error::UnsupportedCode(mh.code()))?;
+        let algorithm = OciAlgorithm::from_code(code.into())?;
+        if mh.size() != algorithm.size() {
+            return Err(Error::InvalidSize(mh.size().into()));
+        }
+        let mut string = String::with_capacity(algorithm.name().len() + 1 + 2 * mh.digest().len());
+        string.push_str(algorithm.name());
+        string.push(':');
+        for byte in mh.digest() {
+            write!(string, "{:02x}", byte).expect("writing to a string never fails");
+        }
+        Ok(string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Code, U32};
+
+    const SHA256: &str = "sha256:b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+
+    #[test]
+    fn test_oci_roundtrip() {
+        for code in [Code::Sha2_256, Code::Sha2_512].iter() {
+            let hash = code.digest(b"hello world");
+            let oci = Code::to_oci_digest(&hash).unwrap();
+            assert_eq!(Code::from_oci_digest(&oci).unwrap(), hash);
+        }
+        let hash = Code::Sha2_256.digest(b"hello world");
+        assert_eq!(Code::to_oci_digest(&hash).unwrap(), SHA256);
+    }
+
+    #[test]
+    fn test_oci_errors() {
+        let parse = Code::from_oci_digest;
+        assert!(parse(&SHA256.to_uppercase()).is_err(), "uppercase");
+        assert!(
+            parse(&SHA256.replace("sha256", "sha384")).is_err(),
+            "algorithm"
+        );
+        assert!(parse(&SHA256[..SHA256.len() - 2]).is_err(), "too short");
+        assert!(parse(&format!("{}00", SHA256)).is_err(), "too long");
+        assert!(parse(&SHA256.replace(':', "")).is_err(), "separator");
+        assert!(parse(&SHA256.replace('b', "g")).is_err(), "not hex");
+
+        let sha3 = Code::Sha3_256.digest(b"hello world");
+        assert!(matches!(
+            Code::to_oci_digest(&sha3),
+            Err(Error::UnsupportedCode(0x16))
+        ));
+        let truncated = Multihash::<U32>::wrap(0x12, &[0; 20]).unwrap();
+        assert!(matches!(
+            Code::to_oci_digest(&truncated),
+            Err(Error::InvalidSize(20))
+        ));
+    }
+
+    #[test]
+    fn test_oci_code_table() {
+        #[derive(Clone, Copy, Debug, Eq, tiny_multihash_derive::Multihash, PartialEq)]
+        #[mh(alloc_size = U32)]
+        enum Sha256Code {
+            #[mh(code = codes::SHA2_256, hasher = crate::Sha2_256)]
+            Sha2_256,
+        }
+
+        let hash = Sha256Code::from_oci_digest(SHA256).unwrap();
+        assert_eq!(hash, Sha256Code::Sha2_256.digest(b"hello world"));
+        assert_eq!(Sha256Code::to_oci_digest(&hash).unwrap(), SHA256);
+
+        let sha512 = Code::to_oci_digest(&Code::Sha2_512.digest(b"hello world")).unwrap();
+        assert!(matches!(
+            Sha256Code::from_oci_digest(&sha512),
+            Err(Error::UnsupportedCode(0x13))
+        ));
+        let sha512 = Code::Sha2_512.digest(b"hello world");
+        assert!(matches!(
+            Sha256Code::to_oci_digest(&sha512),
+            Err(Error::UnsupportedCode(0x13))
+        ));
+    }
+}
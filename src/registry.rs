@@ -0,0 +1,218 @@
+//! A registry of hashers that can be extended at runtime.
+use crate::hasher::{DynHasher, HasherVisitor, Size, StatefulHasher};
+use crate::multihash::{digest_matches, Multihash, MultihashCode};
+use crate::Error;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+
+/// Creates a new hasher.
+type Factory = Box<dyn Fn() -> Box<dyn DynHasher> + Send + Sync>;
+
+/// Creates the factory for the hasher of a code.
+struct HasherFactory;
+
+impl HasherVisitor for HasherFactory {
+    type Output = Factory;
+
+    fn visit<H: StatefulHasher + Clone + 'static>(self) -> Self::Output {
+        Box::new(|| Box::new(H::default()))
+    }
+}
+
+/// Maps multihash codes to hashers.
+///
+/// Unlike a derived code table, hashers can be registered at runtime, e.g. by plugins. The
+/// registry can be seeded with the hashers of a code table.
+///
+/// # Example
+///
+/// ```
+/// use tiny_multihash::{Code, HasherRegistry, Multihash, Sha3_256, U64};
+///
+/// let codes = [Code::Sha2_256, Code::Sha2_512];
+/// let mut registry = HasherRegistry::from_codes(codes.iter().copied()).unwrap();
+/// registry.register_hasher::<Sha3_256>(0x300000);
+///
+/// let hash: Multihash<U64> = registry.digest(0x300000, b"hello world").unwrap();
+/// assert!(registry.verify(&hash, b"hello world").is_ok());
+/// assert!(registry.hasher(0x16).is_err());
+/// ```
+#[derive(Default)]
+pub struct HasherRegistry {
+    factories: BTreeMap<u64, Factory>,
+}
+
+impl HasherRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry with the hashers of the given codes of a code table.
+    ///
+    /// It errors with [`Error::UnsupportedCode`] if a code has no hash function.
+    pub fn from_codes<C: MultihashCode>(codes: impl IntoIterator<Item = C>) -> Result<Self, Error> {
+        let mut registry = Self::new();
+        for code in codes {
+            registry.register_code(code)?;
+        }
+        Ok(registry)
+    }
+
+    /// Registers a factory for the hasher of `code`.
+    ///
+    /// A previously registered hasher of the same code is replaced.
+    pub fn register<F>(&mut self, code: u64, factory: F)
+    where
+        F: Fn() -> Box<dyn DynHasher> + Send + Sync + 'static,
+    {
+        self.factories.insert(code, Box::new(factory));
+    }
+
+    /// Registers the hasher `H` for `code`.
+    pub fn register_hasher<H: StatefulHasher + Clone + 'static>(&mut self, code: u64) {
+        self.factories.insert(code, HasherFactory.visit::<H>());
+    }
+
+    /// Registers the hasher of a code of a code table.
+    ///
+    /// It errors with [`Error::UnsupportedCode`] if the code has no hash function.
+    pub fn register_code<C: MultihashCode>(&mut self, code: C) -> Result<(), Error> {
+        let factory = code.visit_hasher(HasherFactory)?;
+        self.factories.insert(code.into(), factory);
+        Ok(())
+    }
+
+    /// Returns `true` if there is a hasher for `code`.
+    pub fn contains(&self, code: u64) -> bool {
+        self.factories.contains_key(&code)
+    }
+
+    /// Returns the registered codes in ascending order.
+    pub fn codes(&self) -> impl Iterator<Item = u64> + '_ {
+        self.factories.keys().copied()
+    }
+
+    /// Returns a new hasher for `code`.
+    ///
+    /// It errors with [`Error::UnsupportedCode`] if no hasher is registered for the code.
+    pub fn hasher(&self, code: u64) -> Result<Box<dyn DynHasher>, Error> {
+        let factory = self
+            .factories
+            .get(&code)
+            .ok_or(Error::UnsupportedCode(code))?;
+        Ok(factory())
+    }
+
+    /// Calculates the multihash of some input data.
+    ///
+    /// It errors with [`Error::UnsupportedCode`] if no hasher is registered for the code and with
+    /// [`Error::InvalidSize`] if the digest doesn't fit into `S`.
+    pub fn digest<S: Size>(&self, code: u64, input: &[u8]) -> Result<Multihash<S>, Error> {
+        let mut hasher = self.hasher(code)?;
+        hasher.update(input);
+        let mut digest = [0; 255];
+        let len = hasher.finalize_into(&mut digest);
+        Multihash::wrap(code, &digest[..len])
+    }
+
+    /// Verifies that a multihash is the hash of some data.
+    ///
+    /// It works like [`MultihashCode::verify`], but with the registered hashers.
+    pub fn verify<S: Size>(&self, mh: &Multihash<S>, data: &[u8]) -> Result<(), Error> {
+        let mut hasher = self.hasher(mh.code())?;
+        if mh.size() > hasher.size() {
+            return Err(Error::InvalidSize(mh.size().into()));
+        }
+        hasher.update(data);
+        let mut digest = [0; 255];
+        let len = hasher.finalize_into(&mut digest);
+        if digest_matches(hasher.size(), &digest[..len], mh.digest()) {
+            Ok(())
+        } else {
+            Err(Error::DigestMismatch)
+        }
+    }
+}
+
+impl core::fmt::Debug for HasherRegistry {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("HasherRegistry")
+            .field("codes", &self.factories.keys())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Code, Sha3_256, U64};
+
+    const DATA: &[u8] = b"hello world";
+    const CUSTOM: u64 = 0x300000;
+
+    #[test]
+    fn test_registry_from_codes() {
+        let codes = [Code::Sha2_256, Code::Blake2b256, Code::Sha1];
+        let registry = HasherRegistry::from_codes(codes.iter().copied()).unwrap();
+        assert_eq!(registry.codes().collect::<Vec<_>>(), [0x11, 0x12, 0xb220]);
+        for code in codes.iter() {
+            let hash = registry.digest::<U64>((*code).into(), DATA).unwrap();
+            assert_eq!(hash, code.digest(DATA));
+            assert!(registry.verify(&hash, DATA).is_ok());
+            assert!(matches!(
+                registry.verify(&hash, b"hello"),
+                Err(Error::DigestMismatch)
+            ));
+        }
+        assert!(matches!(
+            registry.digest::<U64>(0x16, DATA),
+            Err(Error::UnsupportedCode(0x16))
+        ));
+    }
+
+    #[test]
+    fn test_registry_runtime_hasher() {
+        let mut registry = HasherRegistry::new();
+        assert!(!registry.contains(CUSTOM));
+        registry.register_hasher::<Sha3_256>(CUSTOM);
+        assert!(registry.contains(CUSTOM));
+
+        let hash = registry.digest::<U64>(CUSTOM, DATA).unwrap();
+        assert_eq!(hash.code(), CUSTOM);
+        assert_eq!(hash.digest(), Code::Sha3_256.digest(DATA).digest());
+        let truncated = hash.truncate(20).unwrap();
+        assert!(registry.verify(&truncated, DATA).is_ok());
+        let too_big = Multihash::<U64>::wrap(CUSTOM, &[0; 33]).unwrap();
+        assert!(matches!(
+            registry.verify(&too_big, DATA),
+            Err(Error::InvalidSize(33))
+        ));
+
+        registry.register(CUSTOM, || Box::new(crate::Sha2_256::default()));
+        let hash = registry.digest::<U64>(CUSTOM, DATA).unwrap();
+        assert_eq!(hash.digest(), Code::Sha2_256.digest(DATA).digest());
+    }
+
+    #[test]
+    fn test_dyn_hasher_box_clone() {
+        let mut hasher = HasherRegistry::from_codes(Some(Code::Sha2_256))
+            .unwrap()
+            .hasher(Code::Sha2_256.into())
+            .unwrap();
+        hasher.update(b"hello");
+        let mut forked = hasher.clone();
+        hasher.update(b" world");
+        forked.update(b" there");
+
+        let mut digest = [0; 32];
+        hasher.finalize_into(&mut digest);
+        assert_eq!(&digest[..], Code::Sha2_256.digest(DATA).digest());
+        forked.finalize_into(&mut digest);
+        assert_eq!(&digest[..], Code::Sha2_256.digest(b"hello there").digest());
+
+        hasher.reset();
+        hasher.finalize_into(&mut digest);
+        assert_eq!(&digest[..], Code::Sha2_256.digest(b"").digest());
+    }
+}
@@ -0,0 +1,164 @@
+//! Zero-copy archiving of multihashes with [rkyv].
+//!
+//! [rkyv]: https://rkyv.org
+use core::fmt;
+
+use generic_array::GenericArray;
+use rkyv::bytecheck::CheckBytes;
+use rkyv::rancor::{fail, Fallible, Source};
+use rkyv::{Archive, Deserialize, Place, Portable, Serialize};
+
+use crate::hasher::Size;
+use crate::multihash::{debug_multihash, Multihash};
+
+/// An archived [`Multihash`].
+///
+/// The code is stored as little-endian bytes, hence the archived multihash has no alignment
+/// requirements and the same layout on every platform. The size is validated when the archive is
+/// accessed with `rkyv::access`, it errors if it is bigger than the allocated size.
+///
+/// # Example
+///
+/// ```
+/// use tiny_multihash::{ArchivedMultihash, Code, MultihashCode, U64};
+///
+/// let mh = Code::Sha2_256.digest(b"hello world");
+/// let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&mh).unwrap();
+/// let archived = rkyv::access::<ArchivedMultihash<U64>, rkyv::rancor::Error>(&bytes).unwrap();
+/// assert_eq!(archived.code(), 0x12);
+/// assert_eq!(archived.digest(), mh.digest());
+/// ```
+#[repr(C)]
+pub struct ArchivedMultihash<S: Size> {
+    code: [u8; 8],
+    size: u8,
+    digest: GenericArray<u8, S>,
+}
+
+// SAFETY: The struct only consists of bytes, it has no padding and the same layout on every
+// platform.
+unsafe impl<S: Size> Portable for ArchivedMultihash<S> {}
+
+impl<S: Size> ArchivedMultihash<S> {
+    /// Returns the code of the multihash.
+    pub fn code(&self) -> u64 {
+        u64::from_le_bytes(self.code)
+    }
+
+    /// Returns the actual size of the digest.
+    pub fn size(&self) -> u8 {
+        self.size
+    }
+
+    /// Returns the digest.
+    pub fn digest(&self) -> &[u8] {
+        &self.digest[..self.size as usize]
+    }
+}
+
+impl<S: Size> fmt::Debug for ArchivedMultihash<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        debug_multihash(self.code(), self.digest(), f)
+    }
+}
+
+impl<S: Size, S2: Size> PartialEq<Multihash<S2>> for ArchivedMultihash<S> {
+    fn eq(&self, other: &Multihash<S2>) -> bool {
+        self.code() == other.code() && self.digest() == other.digest()
+    }
+}
+
+impl<S: Size> Archive for Multihash<S> {
+    type Archived = ArchivedMultihash<S>;
+    type Resolver = ();
+
+    fn resolve(&self, _resolver: Self::Resolver, out: Place<Self::Archived>) {
+        let mut digest = GenericArray::default();
+        digest[..self.digest().len()].copy_from_slice(self.digest());
+        let archived = ArchivedMultihash {
+            code: self.code().to_le_bytes(),
+            size: self.size(),
+            digest,
+        };
+        // SAFETY: The archived multihash only consists of bytes, it has no padding.
+        unsafe { out.write_unchecked(archived) }
+    }
+}
+
+impl<S: Size, Ser: Fallible + ?Sized> Serialize<Ser> for Multihash<S> {
+    fn serialize(&self, _serializer: &mut Ser) -> Result<Self::Resolver, Ser::Error> {
+        Ok(())
+    }
+}
+
+impl<S: Size, De: Fallible + ?Sized> Deserialize<Multihash<S>, De> for ArchivedMultihash<S>
+where
+    De::Error: Source,
+{
+    fn deserialize(&self, _deserializer: &mut De) -> Result<Multihash<S>, De::Error> {
+        match Multihash::wrap(self.code(), self.digest()) {
+            Ok(mh) => Ok(mh),
+            Err(_) => fail!(InvalidSize(self.size)),
+        }
+    }
+}
+
+// SAFETY: All byte patterns are valid for the code and the digest, only the size needs to be
+// checked, so that `digest()` doesn't go out of bounds.
+unsafe impl<S: Size, C: Fallible + ?Sized> CheckBytes<C> for ArchivedMultihash<S>
+where
+    C::Error: Source,
+{
+    unsafe fn check_bytes(value: *const Self, _context: &mut C) -> Result<(), C::Error> {
+        let size = *core::ptr::addr_of!((*value).size);
+        if usize::from(size) > S::USIZE {
+            fail!(InvalidSize(size));
+        }
+        Ok(())
+    }
+}
+
+/// The archived size is bigger than the allocated size.
+#[derive(Debug)]
+struct InvalidSize(u8);
+
+impl fmt::Display for InvalidSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid multihash size {}.", self.0)
+    }
+}
+
+impl core::error::Error for InvalidSize {}
+
+#[cfg(test)]
+mod tests {
+    use super::ArchivedMultihash;
+    use crate::{Multihash, U32, U64};
+    use quickcheck::quickcheck;
+    use rkyv::rancor::Error;
+
+    quickcheck! {
+        fn prop_roundtrip(mh: Multihash<U64>) -> bool {
+            let bytes = rkyv::to_bytes::<Error>(&mh).unwrap();
+            let archived = rkyv::access::<ArchivedMultihash<U64>, Error>(&bytes).unwrap();
+            let mh2 = rkyv::deserialize::<Multihash<U64>, Error>(archived).unwrap();
+            archived == &mh
+                && archived.code() == mh.code()
+                && archived.size() == mh.size()
+                && archived.digest() == mh.digest()
+                && mh2 == mh
+        }
+    }
+
+    #[test]
+    fn test_invalid_size() {
+        let mh = Multihash::<U32>::wrap(0x12, &[1, 2, 3]).unwrap();
+        let mut bytes = rkyv::to_bytes::<Error>(&mh).unwrap();
+        assert_eq!(bytes.len(), 8 + 1 + 32);
+        assert_eq!(bytes[8], 3);
+        bytes[8] = 32;
+        assert!(rkyv::access::<ArchivedMultihash<U32>, Error>(&bytes).is_ok());
+        bytes[8] = 33;
+        assert!(rkyv::access::<ArchivedMultihash<U32>, Error>(&bytes).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+//! SCALE codec support for multihashes and the digest types.
+//!
+//! Digests are encoded like byte slices, i.e. the compact encoded length followed by the bytes.
+//! Multihashes are encoded as the compact encoded code followed by the digest.
+use generic_array::GenericArray;
+use parity_scale_codec::{
+    Compact, CompactLen, Decode, Encode, EncodeLike, Error, Input, MaxEncodedLen, Output,
+};
+
+use crate::hasher::{Digest, Size};
+use crate::multihash::Multihash;
+
+impl<S: Size> Encode for Multihash<S> {
+    fn size_hint(&self) -> usize {
+        let len = self.digest().len();
+        Compact::<u64>::compact_len(&self.code()) + Compact::<u32>::compact_len(&(len as u32)) + len
+    }
+
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        Compact(self.code()).encode_to(dest);
+        encode_digest(self.digest(), dest);
+    }
+}
+
+impl<S: Size> EncodeLike for Multihash<S> {}
+
+impl<S: Size> Decode for Multihash<S> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        let code = <Compact<u64>>::decode(input)?.0;
+        let (bytes, len) = decode_bytes::<S, _>(input)?;
+        Self::wrap(code, &bytes[..len]).map_err(|_| "Invalid digest size".into())
+    }
+}
+
+impl<S: Size> MaxEncodedLen for Multihash<S> {
+    fn max_encoded_len() -> usize {
+        Compact::<u64>::max_encoded_len() + max_encoded_digest_len::<S>()
+    }
+}
+
+/// Encodes the bytes of a digest.
+pub(crate) fn encode_digest<O: Output + ?Sized>(digest: &[u8], dest: &mut O) {
+    digest.encode_to(dest)
+}
+
+/// Decodes a digest that was encoded with [`encode_digest`].
+pub(crate) fn decode_digest<S, D, I>(input: &mut I) -> Result<D, Error>
+where
+    S: Size,
+    D: Digest<S>,
+    I: Input,
+{
+    let (bytes, len) = decode_bytes::<S, _>(input)?;
+    D::wrap(&bytes[..len]).map_err(|_| "Invalid digest size".into())
+}
+
+/// Returns the maximum length of a digest that was encoded with [`encode_digest`].
+pub(crate) fn max_encoded_digest_len<S: Size>() -> usize {
+    Compact::<u32>::compact_len(&(S::USIZE as u32)) + S::USIZE
+}
+
+/// Decodes length-prefixed bytes of at most `S` bytes.
+///
+/// Returns the bytes and their length.
+fn decode_bytes<S: Size, I: Input>(input: &mut I) -> Result<(GenericArray<u8, S>, usize), Error> {
+    let len = <Compact<u32>>::decode(input)?.0 as usize;
+    let mut bytes = GenericArray::<u8, S>::default();
+    if len > bytes.len() || len > u8::MAX as usize {
+        return Err("Digest is bigger than the allocated size".into());
+    }
+    input.read(&mut bytes[..len])?;
+    Ok((bytes, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Code, Hasher, Identity256, IdentityDigest, Multihash, MultihashCode};
+    use crate::{Sha2Digest, Sha2_256, U16, U20, U32, U64};
+    use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+
+    #[test]
+    fn test_digest() {
+        let digest = Sha2_256::digest(b"hello world");
+        let bytes = digest.encode();
+        // Compact encoded length of 32
+        assert_eq!(bytes[0], 32 << 2);
+        assert_eq!(&bytes[1..], digest.as_ref());
+        assert_eq!(Sha2Digest::<U32>::decode(&mut &bytes[..]).unwrap(), digest);
+        assert!(Sha2Digest::<U64>::decode(&mut &bytes[..]).is_err());
+        assert!(Sha2Digest::<U32>::decode(&mut &bytes[..16]).is_err());
+
+        let digest = Identity256::digest(b"hello");
+        let bytes = digest.encode();
+        assert_eq!(bytes, b"\x14hello");
+        assert_eq!(
+            IdentityDigest::<U32>::decode(&mut &bytes[..]).unwrap(),
+            digest
+        );
+    }
+
+    #[test]
+    fn test_multihash() {
+        let mh = Multihash::<U32>::wrap(0x12, &[1, 2, 3]).unwrap();
+        let bytes = mh.encode();
+        assert_eq!(bytes, [0x12 << 2, 3 << 2, 1, 2, 3]);
+        assert_eq!(bytes.len(), mh.size_hint());
+        assert_eq!(Multihash::<U32>::decode(&mut &bytes[..]).unwrap(), mh);
+        assert_eq!(Multihash::<U20>::decode(&mut &bytes[..]).unwrap(), mh);
+
+        // Any allocation size can be encoded
+        let mh = Code::Sha2_256.digest(b"hello world");
+        let bytes = mh.encode();
+        assert_eq!(bytes.len(), 1 + 1 + 32);
+        assert_eq!(Multihash::<U64>::decode(&mut &bytes[..]).unwrap(), mh);
+        assert!(Multihash::<U16>::decode(&mut &bytes[..]).is_err());
+        assert!(Multihash::<U64>::decode(&mut &bytes[..20]).is_err());
+
+        let mh = Multihash::<U64>::wrap(u64::MAX, &[0xff; 64]).unwrap();
+        let bytes = mh.encode();
+        assert_eq!(Multihash::<U64>::decode(&mut &bytes[..]).unwrap(), mh);
+    }
+
+    #[test]
+    fn test_max_encoded_len() {
+        // A 9 byte compact code, a 2 byte compact size and the digest
+        assert_eq!(Multihash::<U64>::max_encoded_len(), 9 + 2 + 64);
+        assert_eq!(Multihash::<U32>::max_encoded_len(), 9 + 1 + 32);
+        let mh = Multihash::<U64>::wrap(u64::MAX, &[0xff; 64]).unwrap();
+        assert_eq!(mh.encode().len(), Multihash::<U64>::max_encoded_len());
+        assert_eq!(Sha2Digest::<U32>::max_encoded_len(), 1 + 32);
+    }
+}
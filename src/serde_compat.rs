@@ -0,0 +1,99 @@
+//! Serde support for the struct form of multihashes of older versions.
+//!
+//! Older versions serialized a [`Multihash`] as struct of the `code`, the `size` and the full
+//! zero-padded digest. Use this module with `#[serde(with = "tiny_multihash::serde_compat")]` in
+//! order to read (and write) data of those versions.
+//!
+//! # Example
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use tiny_multihash::{Multihash, U64};
+//!
+//! #[derive(Deserialize, Serialize)]
+//! struct Config {
+//!     #[serde(with = "tiny_multihash::serde_compat")]
+//!     hash: Multihash<U64>,
+//! }
+//!
+//! let json = format!(r#"{{"hash":{{"code":18,"size":3,"digest":[1,2,3{}]}}}}"#, ",0".repeat(61));
+//! let config: Config = serde_json::from_str(&json).unwrap();
+//! assert_eq!(config.hash, Multihash::<U64>::wrap(0x12, &[1, 2, 3]).unwrap());
+//! assert_eq!(serde_json::to_string(&config).unwrap(), json);
+//! ```
+use generic_array::GenericArray;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::hasher::Size;
+use crate::multihash::Multihash;
+
+/// The struct form of older versions.
+#[derive(Deserialize, Serialize)]
+#[serde(rename = "Multihash", bound = "S: Size")]
+struct Legacy<S: Size> {
+    code: u64,
+    size: u8,
+    digest: GenericArray<u8, S>,
+}
+
+/// Serializes a multihash in the struct form of older versions.
+pub fn serialize<S, Ser>(mh: &Multihash<S>, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+where
+    S: Size,
+    Ser: Serializer,
+{
+    let mut digest = GenericArray::<u8, S>::default();
+    digest[..mh.digest().len()].copy_from_slice(mh.digest());
+    Legacy {
+        code: mh.code(),
+        size: mh.size(),
+        digest,
+    }
+    .serialize(serializer)
+}
+
+/// Deserializes a multihash from the struct form of older versions.
+///
+/// The padding of the digest is ignored.
+pub fn deserialize<'de, S, De>(deserializer: De) -> Result<Multihash<S>, De::Error>
+where
+    S: Size,
+    De: Deserializer<'de>,
+{
+    let legacy = Legacy::<S>::deserialize(deserializer)?;
+    let digest = legacy
+        .digest
+        .get(..legacy.size as usize)
+        .ok_or_else(|| de::Error::custom(crate::Error::InvalidSize(legacy.size.into())))?;
+    Multihash::wrap(legacy.code, digest).map_err(de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Hasher, Multihash, Sha2_256, U32, U64};
+
+    #[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+    struct Wrapper(#[serde(with = "super")] Multihash<U32>);
+
+    #[test]
+    fn test_legacy() {
+        let digest = Sha2_256::digest(b"hello world");
+        let mh = Wrapper(Multihash::wrap(0x12, digest.as_ref()).unwrap());
+        let cbor = serde_cbor::to_vec(&mh).unwrap();
+        assert_eq!(serde_cbor::from_slice::<Wrapper>(&cbor).unwrap(), mh);
+
+        let json = format!(
+            r#"{{"code":18,"size":33,"digest":[{}]}}"#,
+            vec!["0"; 32].join(",")
+        );
+        assert!(serde_json::from_str::<Wrapper>(&json).is_err());
+    }
+
+    #[test]
+    fn test_legacy_padding() {
+        let json = format!(r#"{{"code":0,"size":1,"digest":[1{}]}}"#, ",255".repeat(63));
+        let mh: Multihash<U64> =
+            super::deserialize(&mut serde_json::Deserializer::from_str(&json)).unwrap();
+        assert_eq!(mh, Multihash::<U64>::wrap(0, &[1]).unwrap());
+    }
+}
@@ -0,0 +1,232 @@
+//! Serde support for multihashes and the digest types.
+//!
+//! Multihashes are serialized as multibase strings in human-readable formats and as their
+//! canonical bytes otherwise. Digests are serialized as lowercase hex strings in human-readable
+//! formats and as bytes otherwise.
+use core::fmt;
+use core::marker::PhantomData;
+
+use generic_array::GenericArray;
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::{Serialize, Serializer};
+
+use crate::hasher::{Digest, Size};
+use crate::multibase::{self, Base};
+use crate::multihash::{Multihash, MAX_ENCODED_LEN};
+
+impl<S: Size> Serialize for Multihash<S> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            let mut bytes = [0; MAX_ENCODED_LEN];
+            let len = self
+                .write_to_slice(&mut bytes)
+                .expect("the buffer has the maximum encoded length");
+            serializer.serialize_bytes(&bytes[..len])
+        }
+    }
+}
+
+impl<'de, S: Size> Deserialize<'de> for Multihash<S> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        let visitor = MultihashVisitor(PhantomData);
+        // Human-readable formats might also represent the bytes as string or sequence
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(visitor)
+        } else {
+            deserializer.deserialize_bytes(visitor)
+        }
+    }
+}
+
+struct MultihashVisitor<S>(PhantomData<S>);
+
+impl<'de, S: Size> Visitor<'de> for MultihashVisitor<S> {
+    type Value = Multihash<S>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a multibase encoded multihash or multihash bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, string: &str) -> Result<Self::Value, E> {
+        Multihash::from_str_any_base(string).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        Multihash::from_bytes(bytes).map_err(E::custom)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = [0; MAX_ENCODED_LEN];
+        let mut len = 0;
+        while let Some(byte) = seq.next_element()? {
+            if len == bytes.len() {
+                return Err(de::Error::invalid_length(len + 1, &self));
+            }
+            bytes[len] = byte;
+            len += 1;
+        }
+        self.visit_bytes(&bytes[..len])
+    }
+}
+
+/// Formats bytes as lowercase hex.
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Serializes the bytes of a digest.
+pub(crate) fn serialize_digest<Ser: Serializer>(
+    digest: &[u8],
+    serializer: Ser,
+) -> Result<Ser::Ok, Ser::Error> {
+    if serializer.is_human_readable() {
+        serializer.collect_str(&Hex(digest))
+    } else {
+        serializer.serialize_bytes(digest)
+    }
+}
+
+/// Deserializes a digest that was serialized with [`serialize_digest`].
+pub(crate) fn deserialize_digest<'de, S, D, De>(deserializer: De) -> Result<D, De::Error>
+where
+    S: Size,
+    D: Digest<S>,
+    De: Deserializer<'de>,
+{
+    let visitor = DigestVisitor(PhantomData);
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(visitor)
+    } else {
+        deserializer.deserialize_bytes(visitor)
+    }
+}
+
+struct DigestVisitor<S, D>(PhantomData<(S, D)>);
+
+impl<'de, S: Size, D: Digest<S>> Visitor<'de> for DigestVisitor<S, D> {
+    type Value = D;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a digest of at most {} bytes", S::to_usize())
+    }
+
+    fn visit_str<E: de::Error>(self, hex: &str) -> Result<Self::Value, E> {
+        let mut bytes = GenericArray::<u8, S>::default();
+        let len = multibase::decode_base(Base::Base16Lower, hex, &mut bytes)
+            .or_else(|_| multibase::decode_base(Base::Base16Upper, hex, &mut bytes))
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(hex), &self))?;
+        self.visit_bytes(&bytes[..len])
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        D::wrap(bytes).map_err(|_| E::invalid_length(bytes.len(), &self))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = GenericArray::<u8, S>::default();
+        let mut len = 0;
+        while let Some(byte) = seq.next_element()? {
+            if len == bytes.len() {
+                return Err(de::Error::invalid_length(len + 1, &self));
+            }
+            bytes[len] = byte;
+            len += 1;
+        }
+        self.visit_bytes(&bytes[..len])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Code, Hasher, Multihash, MultihashCode, Sha2Digest, Sha2_256, U32, U64};
+    use crate::{Identity256, IdentityDigest};
+
+    #[test]
+    fn test_digest_human_readable() {
+        let digest = Sha2_256::digest(b"hello world");
+        let json = serde_json::to_string(&digest).unwrap();
+        assert_eq!(
+            json,
+            "\"b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9\""
+        );
+        let digest2: Sha2Digest<U32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(digest, digest2);
+        let digest3: Sha2Digest<U32> = serde_json::from_str(&json.to_uppercase()).unwrap();
+        assert_eq!(digest, digest3);
+
+        assert!(serde_json::from_str::<Sha2Digest<U64>>(&json).is_err());
+        assert!(serde_json::from_str::<Sha2Digest<U32>>("\"b94d\"").is_err());
+        assert!(serde_json::from_str::<Sha2Digest<U32>>("\"xyz\"").is_err());
+
+        let bytes = serde_json::to_string(&digest.as_ref()).unwrap();
+        let digest4: Sha2Digest<U32> = serde_json::from_str(&bytes).unwrap();
+        assert_eq!(digest, digest4);
+    }
+
+    #[test]
+    fn test_digest_binary() {
+        let digest = Sha2_256::digest(b"hello world");
+        let cbor = serde_cbor::to_vec(&digest).unwrap();
+        // Major type 2 (byte string) with a one byte length
+        assert_eq!(&cbor[..2], &[0x58, 32]);
+        assert_eq!(&cbor[2..], digest.as_ref());
+        let digest2: Sha2Digest<U32> = serde_cbor::from_slice(&cbor).unwrap();
+        assert_eq!(digest, digest2);
+    }
+
+    #[test]
+    fn test_identity_digest() {
+        let digest = Identity256::digest(b"hello");
+        let json = serde_json::to_string(&digest).unwrap();
+        assert_eq!(json, "\"68656c6c6f\"");
+        let digest2: IdentityDigest<U32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(digest, digest2);
+        let digest3: IdentityDigest<U32> =
+            serde_cbor::from_slice(&serde_cbor::to_vec(&digest).unwrap()).unwrap();
+        assert_eq!(digest, digest3);
+    }
+
+    #[test]
+    fn test_multihash_human_readable() {
+        let mh = Code::Sha2_256.digest(b"hello world");
+        let json = serde_json::to_string(&mh).unwrap();
+        assert_eq!(json, "\"zQmaozNR7DZHQK1ZcU9p7QdrshMvXqWK6gpu5rmrkPdT3L4\"");
+        let mh2: Multihash<U64> = serde_json::from_str(&json).unwrap();
+        assert_eq!(mh, mh2);
+        // Any supported base is accepted
+        let json = format!("\"{}\"", mh.to_string_of_base(crate::Base::Base32Lower));
+        let mh2: Multihash<U32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(mh, mh2);
+
+        let mh = Multihash::<U64>::wrap(0x12, &[1, 2, 3]).unwrap();
+        assert!(serde_json::from_str::<Multihash<U64>>("\"f12030102\"").is_err());
+        assert!(serde_json::from_str::<Multihash<U64>>("{}").is_err());
+        let mh2: Multihash<U64> = serde_json::from_str("\"f1203010203\"").unwrap();
+        assert_eq!(mh, mh2);
+        // The bytes are accepted as well
+        let mh3: Multihash<U64> = serde_json::from_str("[18, 3, 1, 2, 3]").unwrap();
+        assert_eq!(mh, mh3);
+        assert!(serde_json::from_str::<Multihash<U64>>("[18, 3, 1, 2]").is_err());
+    }
+
+    #[test]
+    fn test_multihash_binary() {
+        let mh = Code::Sha2_256.digest(b"hello world");
+        let cbor = serde_cbor::to_vec(&mh).unwrap();
+        // Major type 2 (byte string) with a one byte length
+        assert_eq!(&cbor[..2], &[0x58, 34]);
+        assert_eq!(&cbor[2..], &mh.to_bytes()[..]);
+        let mh2: Multihash<U64> = serde_cbor::from_slice(&cbor).unwrap();
+        assert_eq!(mh, mh2);
+        assert!(serde_cbor::from_slice::<Multihash<crate::U16>>(&cbor).is_err());
+    }
+}
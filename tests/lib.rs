@@ -1,21 +1,27 @@
+use std::convert::TryFrom;
 use std::io::Cursor;
 
+#[cfg(feature = "scale-codec")]
+use tiny_multihash::parity_scale_codec;
+#[cfg(feature = "serde-codec")]
+use tiny_multihash::serde;
 use tiny_multihash::{
-    derive::Multihash, Blake2b256, Blake2b512, Blake2bDigest, Blake2s128, Blake2s256,
-    Blake2sDigest, Blake3Digest, Blake3_256, Digest, Error, Hasher, Identity256, IdentityDigest,
-    Keccak224, Keccak256, Keccak384, Keccak512, KeccakDigest, Multihash, MultihashCode, Sha1,
-    Sha1Digest, Sha2Digest, Sha2_256, Sha2_512, Sha3Digest, Sha3_224, Sha3_256, Sha3_384, Sha3_512,
-    Size, StatefulHasher, Strobe256, Strobe512, StrobeDigest, U16, U20, U28, U32, U48, U64,
+    derive::Multihash, typenum, Blake2b256, Blake2b512, Blake2bDigest, Blake2s128, Blake2s256,
+    Blake2sDigest, Blake3Digest, Blake3_256, Digest, Error, FromHasher, Hasher, HasherVisitor,
+    Identity256, IdentityDigest, Keccak224, Keccak256, Keccak384, Keccak512, KeccakDigest,
+    Multihash, MultihashCode, Sha1, Sha1Digest, Sha2Digest, Sha2_256, Sha2_512, Sha3Digest,
+    Sha3_224, Sha3_256, Sha3_384, Sha3_512, Size, StatefulHasher, Strobe256, Strobe512,
+    StrobeDigest, U16, U20, U28, U32, U48, U64,
 };
 
 #[derive(Clone, Copy, Debug, Eq, Multihash, PartialEq)]
 #[mh(alloc_size = U64)]
 pub enum Code {
-    #[mh(code = 0x00, hasher = Identity256, digest = IdentityDigest<U32>)]
+    #[mh(code = 0x00, name = "identity", hasher = Identity256, digest = IdentityDigest<U32>)]
     Identity,
     #[mh(code = 0x11, hasher = Sha1, digest = Sha1Digest<U20>)]
     Sha1,
-    #[mh(code = 0x12, hasher = Sha2_256, digest = Sha2Digest<U32>)]
+    #[mh(code = tiny_multihash::codes::SHA2_256, name = "sha2-256", hasher = Sha2_256, digest = Sha2Digest<U32>)]
     Sha2_256,
     #[mh(code = 0x13, hasher = Sha2_512, digest = Sha2Digest<U64>)]
     Sha2_512,
@@ -215,16 +221,35 @@ where
     let mut expected_cursor = Cursor::new(&expected_bytes);
     let multihash = code.digest(b"hello world");
 
-    assert_eq!(Multihash::wrap(code.into(), &digest).unwrap(), multihash);
+    assert_eq!(
+        Multihash::<U64>::wrap(code.into(), &digest).unwrap(),
+        multihash
+    );
     assert_eq!(multihash.code(), u64::from(code));
     assert_eq!(multihash.size() as usize, digest.len());
     assert_eq!(multihash.digest(), digest);
-    assert_eq!(Multihash::read(&mut expected_cursor).unwrap(), multihash);
-    assert_eq!(Multihash::from_bytes(&expected_bytes).unwrap(), multihash);
+    assert_eq!(
+        Multihash::<U64>::read(&mut expected_cursor).unwrap(),
+        multihash
+    );
+    assert_eq!(
+        Multihash::<U64>::from_bytes(&expected_bytes).unwrap(),
+        multihash
+    );
     let mut written_buf = Vec::new();
     multihash.write(&mut written_buf).unwrap();
     assert_eq!(written_buf, expected_bytes);
     assert_eq!(multihash.to_bytes(), expected_bytes);
+    assert_eq!(multihash.encoded_len(), expected_bytes.len());
+    let mut slice_buf = vec![0; expected_bytes.len()];
+    assert_eq!(
+        multihash.write_to_slice(&mut slice_buf).unwrap(),
+        expected_bytes.len()
+    );
+    assert_eq!(slice_buf, expected_bytes);
+    let (multihash_from_slice, rest) = Multihash::<U64>::from_slice(&expected_bytes).unwrap();
+    assert_eq!(multihash_from_slice, multihash);
+    assert!(rest.is_empty());
 
     // Test from hasher digest conversion
     let mut hasher = H::default();
@@ -315,11 +340,431 @@ fn test_multihash_methods() {
 }
 
 #[test]
-#[should_panic]
+fn multihash_truncated() {
+    let hash = Code::Sha2_256.digest(b"hello world");
+    let truncated = Code::Sha2_256.digest_truncated(b"hello world", 20).unwrap();
+    assert_eq!(truncated.code(), hash.code());
+    assert_eq!(truncated.size(), 20);
+    assert_eq!(truncated.digest(), &hash.digest()[..20]);
+    assert_eq!(hash.truncate(20).unwrap(), truncated);
+    assert_eq!(hash.truncate(32).unwrap(), hash);
+
+    assert!(matches!(hash.truncate(33), Err(Error::InvalidSize(33))));
+    assert!(matches!(
+        Code::Sha1.digest_truncated(b"hello world", 32),
+        Err(Error::InvalidSize(32))
+    ));
+
+    let decoded = Multihash::<U64>::from_bytes(&truncated.to_bytes()).unwrap();
+    let code = Code::try_from(decoded.code()).unwrap();
+    assert!(code.is_truncated(decoded.size()).unwrap());
+    assert!(!code.is_truncated(hash.size()).unwrap());
+    assert!(matches!(code.is_truncated(33), Err(Error::InvalidSize(33))));
+}
+
+#[test]
+fn multihash_verify() {
+    let data = b"hello world";
+    for code in [
+        Code::Identity,
+        Code::Sha2_256,
+        Code::Sha3_512,
+        Code::Strobe256,
+    ]
+    .iter()
+    {
+        let hash = code.digest(data);
+        assert!(Code::verify(&hash, data).is_ok());
+        assert!(matches!(
+            Code::verify(&hash, b"hello world!"),
+            Err(Error::DigestMismatch)
+        ));
+    }
+
+    let truncated = Code::Sha2_256.digest_truncated(data, 20).unwrap();
+    assert!(Code::verify(&truncated, data).is_ok());
+    assert!(matches!(
+        Code::verify(&truncated, b"hello"),
+        Err(Error::DigestMismatch)
+    ));
+
+    // Data that is bigger than the identity digest is truncated
+    let identity = Code::Identity.digest(&[7; 32]);
+    assert!(Code::verify(&identity, &[7; 32]).is_ok());
+    assert!(Code::verify(&identity, &[7; 100]).is_ok());
+    assert!(matches!(
+        Code::verify(&identity, &[8; 100]),
+        Err(Error::DigestMismatch)
+    ));
+
+    let unknown = Multihash::<U64>::wrap(0x99, &[0; 32]).unwrap();
+    assert!(matches!(
+        Code::verify(&unknown, data),
+        Err(Error::UnsupportedCode(0x99))
+    ));
+    let too_big = Multihash::<U64>::wrap(Code::Sha1.into(), &[0; 32]).unwrap();
+    assert!(matches!(
+        Code::verify(&too_big, data),
+        Err(Error::InvalidSize(32))
+    ));
+}
+
+#[test]
+fn multihash_verifying_reader_identity() {
+    use std::io::{ErrorKind, Read};
+    use tiny_multihash::VerifyingReader;
+
+    let identity = Code::Identity.digest(&[7; 32]);
+    let mut reader = VerifyingReader::<_, Code>::new(&[7; 32][..], &identity).unwrap();
+    assert_eq!(reader.read_to_end(&mut Vec::new()).unwrap(), 32);
+
+    // More data than fits into the identity digest is truncated
+    let mut reader = VerifyingReader::<_, Code>::new(&[7; 100][..], &identity).unwrap();
+    assert_eq!(reader.read_to_end(&mut Vec::new()).unwrap(), 100);
+
+    let mut reader = VerifyingReader::<_, Code>::new(&[8; 100][..], &identity).unwrap();
+    let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(matches!(
+        err.into_inner().unwrap().downcast::<Error>().as_deref(),
+        Ok(Error::DigestMismatch)
+    ));
+}
+
+#[cfg(feature = "futures-io")]
+#[test]
+fn multihash_futures_digest_reader_identity() {
+    use futures::executor::block_on;
+    use futures::io::Cursor;
+    use tiny_multihash::FuturesDigestReader;
+
+    block_on(async {
+        let hash = Code::Identity.digest_reader(Cursor::new([7; 32])).await;
+        assert_eq!(hash.unwrap(), Code::Identity.digest(&[7; 32]));
+        // More data than fits into the identity digest is truncated
+        let hash = Code::Identity.digest_reader(Cursor::new([7; 100])).await;
+        assert_eq!(hash.unwrap(), Code::Identity.digest(&[7; 32]));
+    });
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn multihash_tokio_digest_reader_identity() {
+    use tiny_multihash::TokioDigestReader;
+
+    let hash = Code::Identity.digest_reader(&[7; 32][..]).await;
+    assert_eq!(hash.unwrap(), Code::Identity.digest(&[7; 32]));
+    // More data than fits into the identity digest is truncated
+    let hash = Code::Identity.digest_reader(&[7; 100][..]).await;
+    assert_eq!(hash.unwrap(), Code::Identity.digest(&[7; 32]));
+}
+
+#[test]
+fn multihash_code_hasher() {
+    let data = b"hello world";
+    for code in [
+        Code::Identity,
+        Code::Sha1,
+        Code::Blake2s128,
+        Code::Strobe512,
+    ]
+    .iter()
+    {
+        let mut hasher = code.hasher().unwrap();
+        assert_eq!(hasher.code(), *code);
+        for chunk in data.chunks(4) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finalize(), code.digest(data));
+
+        hasher.reset();
+        hasher.update(b"hello");
+        assert_eq!(hasher.finalize(), code.digest(b"hello"));
+    }
+}
+
+#[test]
+fn multihash_derive_alloc_size_alias() {
+    type AllocSize = U32;
+
+    #[derive(Clone, Copy, Debug, Eq, Multihash, PartialEq)]
+    #[mh(alloc_size = AllocSize)]
+    enum SmallCode {
+        #[mh(code = 0x12, hasher = Sha2_256, digest = Sha2Digest<U32>)]
+        Sha2_256,
+        #[mh(code = 0xb250, hasher = Blake2s128, digest = Blake2sDigest<U16>)]
+        Blake2s128,
+    }
+
+    let digest = Blake2s128::digest(b"hello world");
+    let hash = SmallCode::multihash_from_digest(&digest);
+    assert_eq!(hash, SmallCode::Blake2s128.digest(b"hello world"));
+    assert_eq!(SmallCode::from(&Sha2_256::digest(b"")), SmallCode::Sha2_256);
+}
+
+#[test]
+fn multihash_derive_from_hasher() {
+    use std::io::Write;
+    use tiny_multihash::HashingWriter;
+
+    // Hashers of another crate, with the same digest type of different sizes
+    #[derive(Clone, Copy, Debug, Eq, Multihash, PartialEq)]
+    #[mh(alloc_size = U64)]
+    enum InferredCode {
+        #[mh(code = 0x12, hasher = Sha2_256, digest = Sha2Digest<U32>)]
+        Sha2_256,
+        #[mh(code = 0x13, hasher = Sha2_512, digest = Sha2Digest<U64>)]
+        Sha2_512,
+    }
+
+    assert_eq!(
+        <InferredCode as FromHasher<Sha2_512>>::from_hasher(),
+        InferredCode::Sha2_512
+    );
+    assert_eq!(InferredCode::Sha2_256.size(), 32);
+    assert_eq!(InferredCode::Sha2_512.size(), 64);
+
+    let mut writer = HashingWriter::<_, Sha2_512>::new(Vec::new());
+    writer.write_all(b"hello world").unwrap();
+    let (_, hash) = writer.finish::<InferredCode>();
+    assert_eq!(hash, InferredCode::Sha2_512.digest(b"hello world"));
+    assert_eq!(hash, Code::Sha2_512.digest(b"hello world"));
+}
+
+#[derive(Clone, Copy, Debug, Eq, Multihash, PartialEq)]
+#[mh(alloc_size = U64)]
+#[cfg_attr(feature = "serde-codec", mh(serde))]
+#[cfg_attr(feature = "scale-codec", mh(scale))]
+enum OpenCode {
+    #[mh(code = 0x12, hasher = Sha2_256, digest = Sha2Digest<U32>)]
+    Sha2_256,
+    #[mh(unknown)]
+    Unknown(u64),
+}
+
+#[test]
+fn multihash_unknown_code() {
+    let hash = Code::Sha3_256.digest(b"hello world");
+    let code = OpenCode::try_from(hash.code()).unwrap();
+    assert_eq!(code, OpenCode::Unknown(0x16));
+    assert_eq!(u64::from(code), 0x16);
+    assert_eq!(OpenCode::try_from(0x12).unwrap(), OpenCode::Sha2_256);
+    assert_eq!(OpenCode::ALL, &[OpenCode::Sha2_256]);
+    assert_eq!(code.name(), "unknown");
+    assert_eq!(code.to_string(), "unknown(0x16)");
+    assert_eq!(OpenCode::Sha2_256.to_string(), "Sha2_256");
+    assert_eq!(code.size(), 0);
+
+    assert!(matches!(
+        code.try_digest(b"hello world"),
+        Err(Error::UnsupportedCode(0x16))
+    ));
+    assert!(matches!(
+        code.digest_truncated(b"hello world", 16),
+        Err(Error::UnsupportedCode(0x16))
+    ));
+    assert!(matches!(
+        OpenCode::verify(&hash, b"hello world"),
+        Err(Error::UnsupportedCode(0x16))
+    ));
+    let hash = OpenCode::Sha2_256.try_digest(b"hello world").unwrap();
+    assert!(OpenCode::verify(&hash, b"hello world").is_ok());
+}
+
+#[test]
+fn multihash_unknown_code_hasher() {
+    assert!(matches!(
+        OpenCode::Unknown(0x16).hasher(),
+        Err(Error::UnsupportedCode(0x16))
+    ));
+    let mut hasher = OpenCode::Sha2_256.hasher().unwrap();
+    hasher.update(b"hello world");
+    assert_eq!(
+        hasher.finalize(),
+        OpenCode::Sha2_256.try_digest(b"hello world").unwrap()
+    );
+}
+
+#[cfg(feature = "serde-codec")]
+#[test]
+fn multihash_code_serde() {
+    let json = serde_json::to_string(&OpenCode::Sha2_256).unwrap();
+    assert_eq!(json, "\"Sha2_256\"");
+    assert_eq!(
+        serde_json::from_str::<OpenCode>(&json).unwrap(),
+        OpenCode::Sha2_256
+    );
+    // Unknown codes have no name, they are serialized as integer
+    let json = serde_json::to_string(&OpenCode::Unknown(0x16)).unwrap();
+    assert_eq!(json, "22");
+    assert_eq!(
+        serde_json::from_str::<OpenCode>(&json).unwrap(),
+        OpenCode::Unknown(0x16)
+    );
+    assert_eq!(
+        serde_json::from_str::<OpenCode>("18").unwrap(),
+        OpenCode::Sha2_256
+    );
+    assert!(serde_json::from_str::<OpenCode>("\"sha2-256\"").is_err());
+
+    let cbor = serde_cbor::to_vec(&OpenCode::Sha2_256).unwrap();
+    assert_eq!(cbor, [0x12]);
+    assert_eq!(
+        serde_cbor::from_slice::<OpenCode>(&cbor).unwrap(),
+        OpenCode::Sha2_256
+    );
+
+    let code = tiny_multihash::Code::Blake2b256;
+    let json = serde_json::to_string(&code).unwrap();
+    assert_eq!(json, "\"blake2b-256\"");
+    assert_eq!(
+        serde_json::from_str::<tiny_multihash::Code>(&json).unwrap(),
+        code
+    );
+    assert!(serde_json::from_str::<tiny_multihash::Code>("3145728").is_err());
+}
+
+#[cfg(feature = "serde-codec")]
+#[test]
+fn multihash_digest_serde() {
+    #[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+    #[serde(crate = "tiny_multihash::serde")]
+    struct Config {
+        code: tiny_multihash::Code,
+        digest: Sha2Digest<U32>,
+    }
+
+    let config = Config {
+        code: tiny_multihash::Code::Sha2_256,
+        digest: Sha2_256::digest(b"hello world"),
+    };
+    let json = serde_json::to_string(&config).unwrap();
+    assert_eq!(
+        json,
+        r#"{"code":"sha2-256","digest":"b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"}"#
+    );
+    assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+    let cbor = serde_cbor::to_vec(&config).unwrap();
+    assert_eq!(serde_cbor::from_slice::<Config>(&cbor).unwrap(), config);
+}
+
+#[cfg(feature = "scale-codec")]
+#[test]
+fn multihash_code_scale() {
+    use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+
+    let bytes = OpenCode::Sha2_256.encode();
+    assert_eq!(bytes, [0x12 << 2]);
+    assert_eq!(
+        OpenCode::decode(&mut &bytes[..]).unwrap(),
+        OpenCode::Sha2_256
+    );
+    let bytes = OpenCode::Unknown(u64::MAX).encode();
+    assert_eq!(bytes.len(), OpenCode::max_encoded_len());
+    let bytes = OpenCode::Unknown(0x300012).encode();
+    assert_eq!(
+        OpenCode::decode(&mut &bytes[..]).unwrap(),
+        OpenCode::Unknown(0x300012)
+    );
+    let bytes = tiny_multihash::Code::Sha2_256.encode();
+    assert_eq!(
+        tiny_multihash::Code::decode(&mut &bytes[..]).unwrap(),
+        tiny_multihash::Code::Sha2_256
+    );
+    let bytes = OpenCode::Unknown(0x300000).encode();
+    assert!(tiny_multihash::Code::decode(&mut &bytes[..]).is_err());
+}
+
+#[derive(Clone, Copy, Debug, Eq, Multihash, PartialEq)]
+#[mh(alloc_size = U64)]
+enum AliasCode {
+    #[mh(code = 0x12, alias = 0x300012, alias = 0x300013, hasher = Sha2_256, digest = Sha2Digest<U32>)]
+    Sha2_256,
+    #[mh(code = 0x13, hasher = Sha2_512, digest = Sha2Digest<U64>, deprecated)]
+    Sha2_512,
+}
+
+#[test]
+fn multihash_code_aliases() {
+    for code in &[0x12, 0x300012, 0x300013] {
+        let decoded = AliasCode::try_from(*code).unwrap();
+        assert_eq!(decoded, AliasCode::Sha2_256);
+        assert_eq!(u64::from(decoded), 0x12);
+    }
+    assert!(AliasCode::try_from(0x300014).is_err());
+
+    let hash = AliasCode::Sha2_256.digest(b"hello world");
+    assert_eq!(hash.code(), 0x12);
+    let alias = Multihash::<U64>::wrap(0x300012, hash.digest()).unwrap();
+    assert!(AliasCode::verify(&alias, b"hello world").is_ok());
+    let alias = Multihash::<U64>::from_bytes(&alias.to_bytes()).unwrap();
+    assert_eq!(
+        AliasCode::try_from(alias.code()).unwrap(),
+        AliasCode::Sha2_256
+    );
+}
+
+#[test]
+fn multihash_code_deprecated() {
+    assert!(!AliasCode::Sha2_256.is_deprecated());
+    assert!(AliasCode::Sha2_512.is_deprecated());
+    assert!(!OpenCode::Unknown(0x16).is_deprecated());
+    // Deprecated codes can still be used
+    let hash = AliasCode::Sha2_512.digest(b"hello world");
+    assert!(AliasCode::verify(&hash, b"hello world").is_ok());
+}
+
+#[test]
+fn multihash_code_names() {
+    assert_eq!(Code::ALL.len(), 19);
+    for code in Code::ALL.iter() {
+        assert_eq!(code.name().parse::<Code>().unwrap(), *code);
+        assert_eq!(code.to_string(), code.name());
+    }
+    assert_eq!(Code::Identity.name(), "identity");
+    assert_eq!(Code::Sha2_256.to_string(), "sha2-256");
+    // Without a `name` attribute the name of the variant is used
+    assert_eq!(Code::Sha2_512.name(), "Sha2_512");
+    assert!(matches!(
+        "sha2-512".parse::<Code>(),
+        Err(Error::UnsupportedName)
+    ));
+
+    let code: tiny_multihash::Code = "blake2b-256".parse().unwrap();
+    assert_eq!(code, tiny_multihash::Code::Blake2b256);
+    assert_eq!(tiny_multihash::Code::Keccak224.to_string(), "keccak-224");
+    assert_eq!(tiny_multihash::Code::ALL[0], tiny_multihash::Code::Sha1);
+}
+
+#[test]
+fn multihash_literal() {
+    use tiny_multihash::derive::multihash;
+
+    const HELLO_WORLD: Multihash<U64> =
+        multihash!("1220b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9");
+    static HELLO_WORLD_BASE58: Multihash<U64> =
+        multihash!("zQmaozNR7DZHQK1ZcU9p7QdrshMvXqWK6gpu5rmrkPdT3L4");
+    const CODE: u64 = HELLO_WORLD.code();
+    const SIZE: u8 = HELLO_WORLD.size();
+
+    assert_eq!(CODE, u64::from(Code::Sha2_256));
+    assert_eq!(SIZE, 32);
+    assert_eq!(HELLO_WORLD, Code::Sha2_256.digest(b"hello world"));
+    assert_eq!(HELLO_WORLD_BASE58, HELLO_WORLD);
+}
+
+#[test]
 fn test_long_identity_hash() {
-    // The identity hash panics if the input size is bigger than the maximum size
+    // The identity hash truncates input that is bigger than the maximum size
     let input = b"abcdefghijklmnopqrstuvwxyz abcdefghijklmnopqrstuvwxyz";
-    Identity256::digest(input);
+    assert_eq!(Identity256::digest(input).as_ref(), &input[..32]);
+
+    let mut hasher = Identity256::default();
+    hasher.update(&input[..20]);
+    hasher.update(&input[20..]);
+    hasher.update(input);
+    assert_eq!(hasher.finalize().as_ref(), &input[..32]);
+    assert!(Code::Identity.try_digest(input).is_ok());
 }
 
 #[test]
@@ -346,4 +791,8 @@ fn multihash_errors() {
         Multihash::<U64>::from_bytes(&[identity_code, identity_length, 1, 2, 3, 4]).is_err(),
         "Should error on wrong hash length"
     );
+    assert!(
+        Multihash::<U64>::from_slice(&[identity_code, identity_length, 1, 2]).is_err(),
+        "Should error on a slice that is shorter than the hash length"
+    );
 }